@@ -0,0 +1,602 @@
+//! `#[meta(...)]` attributes, shared by `msg_chain_derive` and `from_chain_derive`
+use std::iter::FromIterator;
+
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, Data, DataEnum, Fields, Generics, Ident, Index, Lit, LitStr, Member, Meta,
+    NestedMeta, Path, Type, WherePredicate,
+};
+
+/// how to fill a field when the key not exist
+pub enum FieldDefault {
+    /// `#[meta(default)]`, use `Default::default()`
+    Trait,
+    /// `#[meta(default = "path")]`, call `path()`
+    Path(Path),
+}
+
+/// attributes on a field
+#[derive(Default)]
+pub struct FieldAttr {
+    /// `#[meta(rename = "key")]`
+    pub rename: Option<String>,
+    /// `#[meta(alias = "key")]`, can be used many times
+    pub alias: Vec<String>,
+    /// `#[meta(skip)]`
+    pub skip: bool,
+    pub default: Option<FieldDefault>,
+    /// `#[meta(numeric_strings)]`, numbers in this field can be loaded from string
+    pub numeric_strings: bool,
+    /// `#[meta(lenient)]` => `Some(true)`, `#[meta(strict)]` => `Some(false)`,
+    /// whether bad elements of collection in this field are dropped
+    pub lenient: Option<bool>,
+}
+
+/// `#[meta(rename_all = "...")]`, how field name become key
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum RenameRule {
+    /// `image_id` => `imageId`, the default one
+    #[default]
+    CamelCase,
+    /// `image_id` => `image_id`
+    SnakeCase,
+    /// `image_id` => `ImageId`
+    PascalCase,
+    /// `image_id` => `IMAGE_ID`
+    ScreamingSnakeCase,
+    /// keep the field name as it is
+    None,
+}
+
+impl RenameRule {
+    fn from_meta(meta: &Meta) -> syn::Result<Self> {
+        match lit_str(meta)?.value().as_str() {
+            "camelCase" => Ok(RenameRule::CamelCase),
+            "snake_case" => Ok(RenameRule::SnakeCase),
+            "PascalCase" => Ok(RenameRule::PascalCase),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnakeCase),
+            "none" => Ok(RenameRule::None),
+            _ => Err(syn::Error::new_spanned(
+                meta,
+                "rename_all expect one of \"camelCase\", \"snake_case\", \"PascalCase\", \"SCREAMING_SNAKE_CASE\", \"none\"",
+            )),
+        }
+    }
+
+    pub fn apply(&self, name: &str) -> String {
+        let name = name.trim_start_matches("r#");
+        match self {
+            RenameRule::CamelCase => transfrom_name(name),
+            RenameRule::SnakeCase => name.to_lowercase(),
+            RenameRule::PascalCase => {
+                let camel = transfrom_name(name);
+                let mut chars = camel.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => camel,
+                }
+            }
+            RenameRule::ScreamingSnakeCase => name.to_uppercase(),
+            RenameRule::None => name.to_string(),
+        }
+    }
+
+    /// same as `apply`, but `name` is a PascalCase variant name like `ShowLove`
+    pub fn apply_variant(&self, name: &str) -> String {
+        let mut snake = String::new();
+        for (i, ch) in name.trim_start_matches("r#").chars().enumerate() {
+            if ch.is_uppercase() && i > 0 {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        }
+        match self {
+            RenameRule::None => name.to_string(),
+            rule => rule.apply(&snake),
+        }
+    }
+}
+
+/// attributes on a struct, enum or enum variant
+#[derive(Default)]
+pub struct ContainerAttr {
+    /// `#[meta(type = "FlashImage")]`, the `type` of chain
+    pub ty: Option<LitStr>,
+    /// `#[meta(type_alias = "...")]`, other `type` can be loaded
+    pub type_alias: Vec<String>,
+    pub rename_all: Option<RenameRule>,
+    /// `#[meta(validate)]`, check `ChainValidate` after loaded, added by `#[chain_meta_limit]`
+    pub validate: bool,
+    /// `#[meta(crate = "...")]`, path of `msg_chain` when it is re-exported
+    pub krate: Option<Path>,
+    /// `#[meta(serde)]`, `Serialize` with `MessageChain`, `Deserialize` with `LoadFormMap`
+    pub serde: bool,
+}
+
+impl ContainerAttr {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut res = ContainerAttr::default();
+        for item in meta_items(attrs)? {
+            let meta = match &item {
+                NestedMeta::Meta(m) => m,
+                NestedMeta::Lit(l) => return Err(syn::Error::new_spanned(l, "unexpected literal")),
+            };
+            let path = meta.path();
+            if path.is_ident("type") {
+                set_once(&mut res.ty, path, lit_str(meta)?)?;
+            } else if path.is_ident("type_alias") {
+                res.type_alias.push(lit_str(meta)?.value());
+            } else if path.is_ident("rename_all") {
+                set_once(&mut res.rename_all, path, RenameRule::from_meta(meta)?)?;
+            } else if path.is_ident("validate") {
+                res.validate = true;
+            } else if path.is_ident("crate") {
+                set_once(&mut res.krate, path, lit_str(meta)?.parse()?)?;
+            } else if path.is_ident("serde") {
+                res.serde = true;
+            } else {
+                return Err(syn::Error::new_spanned(path, "unknown meta attribute"));
+            }
+        }
+        Ok(res)
+    }
+
+    /// the `type` of chain, default the name of type
+    pub fn type_name(&self, ident: &Ident) -> String {
+        match &self.ty {
+            Some(ty) => ty.value(),
+            None => ident.to_string(),
+        }
+    }
+
+    pub fn rule(&self) -> RenameRule {
+        self.rename_all.unwrap_or_default()
+    }
+
+    /// path generated code use to reach `msg_chain`, default `::msg_chain`
+    pub fn crate_path(&self) -> Path {
+        match &self.krate {
+            Some(path) => path.clone(),
+            None => syn::parse_quote!(::msg_chain),
+        }
+    }
+}
+
+/// a field with the key it used in chain
+pub struct MetaField {
+    /// name of the field, `__field0` for the fields of tuple struct
+    pub ident: Ident,
+    /// `self.#member` reach the field
+    pub member: Member,
+    pub ty: Type,
+    pub key: String,
+    pub attr: FieldAttr,
+}
+
+/// all items inside `#[meta(...)]`
+pub fn meta_items(attrs: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut items = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("meta")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => items.extend(list.nested),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "expected attribute arguments in parentheses: #[meta(...)]",
+                ))
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// set a attribute can only be used once
+fn set_once<T>(slot: &mut Option<T>, path: &Path, value: T) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new_spanned(path, "duplicate meta attribute"));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// the string literal of `name = "value"`
+pub fn lit_str(meta: &Meta) -> syn::Result<LitStr> {
+    if let Meta::NameValue(nv) = meta {
+        if let Lit::Str(s) = &nv.lit {
+            return Ok(s.clone());
+        }
+        return Err(syn::Error::new_spanned(&nv.lit, "expected string literal"));
+    }
+    Err(syn::Error::new_spanned(meta, "expected `name = \"value\"`"))
+}
+
+impl FieldAttr {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut res = FieldAttr::default();
+        for item in meta_items(attrs)? {
+            let meta = match &item {
+                NestedMeta::Meta(m) => m,
+                NestedMeta::Lit(l) => return Err(syn::Error::new_spanned(l, "unexpected literal")),
+            };
+            let path = meta.path();
+            if path.is_ident("rename") {
+                set_once(&mut res.rename, path, lit_str(meta)?.value())?;
+            } else if path.is_ident("alias") {
+                res.alias.push(lit_str(meta)?.value());
+            } else if path.is_ident("skip") {
+                res.skip = true;
+            } else if path.is_ident("numeric_strings") {
+                res.numeric_strings = true;
+            } else if path.is_ident("strict") || path.is_ident("lenient") {
+                if res.lenient.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "only one of `strict` and `lenient` can be used",
+                    ));
+                }
+                res.lenient = Some(path.is_ident("lenient"));
+            } else if path.is_ident("default") {
+                let default = match meta {
+                    Meta::Path(_) => FieldDefault::Trait,
+                    _ => FieldDefault::Path(lit_str(meta)?.parse()?),
+                };
+                set_once(&mut res.default, path, default)?;
+            } else {
+                return Err(syn::Error::new_spanned(path, "unknown meta attribute"));
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// load all fields, fields of tuple struct must be given a key by `#[meta(rename = "...")]`
+///
+/// keys used by two fields are errors, so is the key `type` if `tagged`,
+/// as it is the tag of chain, untagged maps (`IntoChainMeta` struct) can use it
+pub fn load_fields(fields: &Fields, rule: RenameRule, tagged: bool) -> syn::Result<Vec<MetaField>> {
+    let mut res: Vec<MetaField> = Vec::new();
+    for (i, f) in fields.iter().enumerate() {
+        let attr = FieldAttr::from_attrs(&f.attrs)?;
+        let (ident, member) = match &f.ident {
+            Some(ident) => (ident.clone(), Member::Named(ident.clone())),
+            None => (
+                Ident::new(&format!("__field{}", i), f.ty.span()),
+                Member::Unnamed(Index {
+                    index: i as u32,
+                    span: f.ty.span(),
+                }),
+            ),
+        };
+        let key = match (&attr.rename, &f.ident) {
+            (Some(name), _) => name.clone(),
+            (None, Some(ident)) => rule.apply(&ident.to_string()),
+            (None, None) if attr.skip => i.to_string(),
+            (None, None) => {
+                return Err(syn::Error::new_spanned(
+                    f,
+                    "field of tuple struct need a key, use `#[meta(rename = \"...\")]`",
+                ))
+            }
+        };
+        let field = MetaField {
+            ident,
+            member,
+            ty: f.ty.clone(),
+            key,
+            attr,
+        };
+        if !field.attr.skip {
+            check_keys(&res, &field, tagged)?;
+        }
+        res.push(field);
+    }
+    Ok(res)
+}
+
+/// keys `field` can be loaded from
+fn field_keys(field: &MetaField) -> impl Iterator<Item = &String> {
+    std::iter::once(&field.key).chain(field.attr.alias.iter())
+}
+
+fn check_keys(loaded: &[MetaField], field: &MetaField, tagged: bool) -> syn::Result<()> {
+    for key in field_keys(field) {
+        if tagged && key == "type" {
+            return Err(syn::Error::new_spanned(
+                &field.ident,
+                "key `type` is the tag of chain, use `#[meta(rename = \"...\")]` for this field",
+            ));
+        }
+        let used = loaded
+            .iter()
+            .filter(|f| !f.attr.skip)
+            .find(|f| field_keys(f).any(|k| k == key));
+        if let Some(used) = used {
+            return Err(syn::Error::new_spanned(
+                &field.ident,
+                format!("key `{}` is already used by field `{}`", key, member_name(&used.member)),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+/// how the fields of struct are written
+#[derive(Clone, Copy, PartialEq)]
+pub enum Style {
+    /// `struct Face { face_id: u32 }`
+    Named,
+    /// `struct Dice(#[meta(rename = "value")] u8)`
+    Tuple,
+    /// `struct AtAll;`
+    Unit,
+}
+
+/// the fields of struct, and how they are written, `tagged` for `MessageChain` and `LoadFormMap`
+pub fn load_struct(
+    data: &Data,
+    ident: &Ident,
+    rule: RenameRule,
+    tagged: bool,
+) -> syn::Result<(Vec<MetaField>, Style)> {
+    match data {
+        Data::Struct(st) => {
+            let style = match &st.fields {
+                Fields::Named(_) => Style::Named,
+                Fields::Unnamed(_) => Style::Tuple,
+                Fields::Unit => Style::Unit,
+            };
+            Ok((load_fields(&st.fields, rule, tagged)?, style))
+        }
+        Data::Enum(_) => Err(syn::Error::new_spanned(ident, "enum should use `load_variants`")),
+        Data::Union(un) => Err(syn::Error::new_spanned(
+            un.union_token,
+            "union can not be a message, use struct or enum",
+        )),
+    }
+}
+
+/// how a enum variant hold its message
+pub enum VariantKind {
+    /// `Plain(Plain)`, a type already impl MessageChain and LoadFormMap
+    Wrap(Type),
+    /// `Face { face_id: u32 }`, the variant is the message
+    Inline(Vec<MetaField>),
+    /// `AtAll`
+    Unit,
+}
+
+/// a enum variant with the `type` it matched
+pub struct MetaVariant {
+    pub ident: Ident,
+    pub kind: VariantKind,
+    pub tag: String,
+    pub type_alias: Vec<String>,
+}
+
+/// load all variants of enum, `container` is the attributes on enum
+pub fn load_variants(data: &DataEnum, container: &ContainerAttr) -> syn::Result<Vec<MetaVariant>> {
+    if let Some(ty) = &container.ty {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "`type` can only be set on struct or enum variant",
+        ));
+    }
+    let variants = data
+        .variants
+        .iter()
+        .map(|v| {
+            let attr = ContainerAttr::from_attrs(&v.attrs)?;
+            if attr.serde {
+                return Err(syn::Error::new_spanned(
+                    &v.ident,
+                    "`serde` can only be set on struct or enum",
+                ));
+            }
+            let rule = attr.rename_all.or(container.rename_all).unwrap_or_default();
+            let kind = match &v.fields {
+                Fields::Unnamed(fs) if fs.unnamed.len() == 1 => {
+                    if attr.ty.is_some() || !attr.type_alias.is_empty() {
+                        return Err(syn::Error::new_spanned(
+                            &v.ident,
+                            "set `type` on the wrapped type instead of the variant",
+                        ));
+                    }
+                    VariantKind::Wrap(fs.unnamed[0].ty.clone())
+                }
+                Fields::Unnamed(fs) => {
+                    return Err(syn::Error::new_spanned(
+                        fs,
+                        "variant can only wrap exactly one message type",
+                    ))
+                }
+                Fields::Named(_) => VariantKind::Inline(load_fields(&v.fields, rule, true)?),
+                Fields::Unit => VariantKind::Unit,
+            };
+            Ok(MetaVariant {
+                tag: attr.type_name(&v.ident),
+                type_alias: attr.type_alias,
+                ident: v.ident.clone(),
+                kind,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // wrapped types may share `type` on purpose, like `type_alias`, only check the inline ones
+    let mut tags: Vec<(&String, &Ident)> = Vec::new();
+    for v in variants.iter().filter(|v| !matches!(v.kind, VariantKind::Wrap(_))) {
+        for tag in std::iter::once(&v.tag).chain(v.type_alias.iter()) {
+            if let Some((_, used)) = tags.iter().find(|(t, _)| *t == tag) {
+                return Err(syn::Error::new_spanned(
+                    &v.ident,
+                    format!("type `{}` is already used by variant `{}`", tag, used),
+                ));
+            }
+            tags.push((tag, &v.ident));
+        }
+    }
+    Ok(variants)
+}
+
+/// the wrapped type of newtype struct like `struct GroupId(u64)`,
+/// a single field with `#[meta(...)]` is a tuple struct instead
+pub fn newtype_field(data: &Data) -> Option<&Type> {
+    match data {
+        Data::Struct(st) => match &st.fields {
+            Fields::Unnamed(fs) if fs.unnamed.len() == 1 => {
+                let field = &fs.unnamed[0];
+                match meta_items(&field.attrs) {
+                    Ok(items) if items.is_empty() => Some(&field.ty),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// a unit variant of C-like enum with the string it map to
+pub struct MetaValue {
+    pub ident: Ident,
+    /// `#[meta(rename = "...")]`, default the variant name
+    pub value: String,
+    /// `#[meta(alias = "...")]`, other strings can be loaded
+    pub alias: Vec<String>,
+}
+
+/// load all variants of C-like enum, `rename_all` on enum apply to the variant names
+pub fn load_values(data: &DataEnum, container: &ContainerAttr) -> syn::Result<Vec<MetaValue>> {
+    if let Some(ty) = &container.ty {
+        return Err(syn::Error::new_spanned(ty, "`type` can not be used on C-like enum"));
+    }
+    let mut res: Vec<MetaValue> = Vec::new();
+    for v in data.variants.iter() {
+        if !matches!(v.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                &v.fields,
+                "only enum with unit variants can be a value, use `MessageChain` for others",
+            ));
+        }
+        let mut value = None;
+        let mut alias = Vec::new();
+        for item in meta_items(&v.attrs)? {
+            let meta = match &item {
+                NestedMeta::Meta(m) => m,
+                NestedMeta::Lit(l) => return Err(syn::Error::new_spanned(l, "unexpected literal")),
+            };
+            let path = meta.path();
+            if path.is_ident("rename") {
+                set_once(&mut value, path, lit_str(meta)?.value())?;
+            } else if path.is_ident("alias") {
+                alias.push(lit_str(meta)?.value());
+            } else {
+                return Err(syn::Error::new_spanned(path, "unknown meta attribute"));
+            }
+        }
+        let name = v.ident.to_string();
+        let value = match (value, container.rename_all) {
+            (Some(value), _) => value,
+            (None, Some(rule)) => rule.apply_variant(&name),
+            (None, None) => name,
+        };
+        for s in std::iter::once(&value).chain(alias.iter()) {
+            let used = res
+                .iter()
+                .find(|u| std::iter::once(&u.value).chain(u.alias.iter()).any(|k| k == s));
+            if let Some(used) = used {
+                return Err(syn::Error::new_spanned(
+                    &v.ident,
+                    format!("value `{}` is already used by variant `{}`", s, used.ident),
+                ));
+            }
+        }
+        res.push(MetaValue {
+            ident: v.ident.clone(),
+            value,
+            alias,
+        });
+    }
+    Ok(res)
+}
+
+/// find whether a type use any of the type params
+struct ParamVisitor<'a> {
+    params: &'a [&'a Ident],
+    found: bool,
+}
+
+impl<'a, 'ast> Visit<'ast> for ParamVisitor<'a> {
+    fn visit_path(&mut self, path: &'ast Path) {
+        if path.leading_colon.is_none() {
+            if let Some(first) = path.segments.first() {
+                if self.params.contains(&&first.ident) {
+                    self.found = true;
+                }
+            }
+        }
+        visit::visit_path(self, path);
+    }
+}
+
+/// add `ty: bound` to the where clause for every type in `tys` using a type param,
+/// concrete types are left for the compiler to check
+pub fn add_bounds<'t, I>(generics: &mut Generics, tys: I, bound: &Path)
+where
+    I: IntoIterator<Item = &'t Type>,
+{
+    let params: Vec<Ident> = generics.type_params().map(|t| t.ident.clone()).collect();
+    if params.is_empty() {
+        return;
+    }
+    let params: Vec<&Ident> = params.iter().collect();
+    let mut predicates: Vec<WherePredicate> = Vec::new();
+    for ty in tys {
+        let mut visitor = ParamVisitor {
+            params: &params,
+            found: false,
+        };
+        visitor.visit_type(ty);
+        if visitor.found {
+            predicates.push(syn::parse_quote!(#ty: #bound));
+        }
+    }
+    generics.make_where_clause().predicates.extend(predicates);
+}
+
+/// `image_id` => `imageId`
+pub fn transfrom_name(name: &str) -> String {
+    name.split('_')
+        .map(|f| f.chars())
+        .map(|mut f| {
+            let mut s = String::new();
+            let first = f.next();
+            let left_str = String::from_iter(f);
+            if let Some(ch) = first {
+                s.push_str(&ch.to_uppercase().to_string());
+                s.push_str(&left_str);
+            }
+            s
+        })
+        .reduce(|mut f, b| {
+            f.push_str(&b);
+            f
+        })
+        .map(|f| {
+            let mut ch_iter = f.chars();
+            let mut t = String::new();
+            if let Some(first) = ch_iter.next() {
+                t.push_str(&first.to_lowercase().to_string());
+            }
+            t.push_str(&String::from_iter(ch_iter));
+            t
+        })
+        .unwrap_or_default()
+}
@@ -1,6 +1,243 @@
+use chain_meta_attr::{ContainerAttr, FieldAttr, RenameRule};
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Data, DeriveInput, Expr, Ident, LitInt, LitStr, Member, Path, Token, Type};
+
+/// check the limits of fields, put it above `#[derive(...)]`
+///
+/// limits are set by `#[limit(...)]` on fields:
+/// `len(min = 1, max = 4500)`, `non_empty`, `range(1..=10000)`, `one_of("a", "b")`, `regex = "..."`
+///
+/// `ChainValidate` will be implemented, and `LoadFormMap` will reject the map that break the limits
 #[proc_macro_attribute]
 pub fn chain_meta_limit(args: TokenStream, input: TokenStream) -> TokenStream {
-    todo!()
+    let args = quote::__private::TokenStream::from(args);
+    if !args.is_empty() {
+        return syn::Error::new_spanned(args, "chain_meta_limit take no arguments")
+            .to_compile_error()
+            .into();
+    }
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+
+    impl_limit_macro(ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn impl_limit_macro(mut ast: DeriveInput) -> syn::Result<quote::__private::TokenStream> {
+    let container = ContainerAttr::from_attrs(&ast.attrs)?;
+    let rule = container.rule();
+    let krate = container.crate_path();
+
+    let fields = match &mut ast.data {
+        Data::Struct(st) => &mut st.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "chain_meta_limit only support struct",
+            ))
+        }
+    };
+
+    let mut checks = Vec::new();
+    for (i, field) in fields.iter_mut().enumerate() {
+        let mut limits = Vec::new();
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("limit")) {
+            let parsed = attr.parse_args_with(Punctuated::<Limit, Token![,]>::parse_terminated)?;
+            limits.extend(parsed);
+        }
+        field.attrs.retain(|a| !a.path.is_ident("limit"));
+        if limits.is_empty() {
+            continue;
+        }
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        };
+        let key = field_key(&member, &FieldAttr::from_attrs(&field.attrs)?, rule);
+        let calls = limits.iter().map(|l| l.check(&krate));
+        let check = quote! {
+            #(
+                #calls.map_err(|r| #krate::ChainError::invalid(r).at_key(#key))?;
+            )*
+        };
+        checks.push(if is_option(&field.ty) {
+            quote! {
+                if let ::std::option::Option::Some(__v) = &self.#member {
+                    #check
+                }
+            }
+        } else {
+            quote! {
+                {
+                    let __v = &self.#member;
+                    #check
+                }
+            }
+        });
+    }
+
+    // let `LoadFormMap` and `FromChainMeta` check after loaded
+    if derives(&ast, "LoadFormMap") || derives(&ast, "FromChainMeta") {
+        ast.attrs.push(syn::parse_quote! {#[meta(validate)]});
+    }
+
+    let name = &ast.ident;
+    let (impl_g, ty_g, where_c) = ast.generics.split_for_impl();
+    Ok(quote! {
+        #ast
+
+        impl #impl_g #krate::ChainValidate for #name #ty_g #where_c {
+            fn validate(&self) -> ::std::result::Result<(), #krate::ChainError> {
+                #(#checks)*
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// the key field used in chain, same as the derives
+fn field_key(member: &Member, attr: &FieldAttr, rule: RenameRule) -> String {
+    match (&attr.rename, member) {
+        (Some(name), _) => name.clone(),
+        (None, Member::Named(ident)) => rule.apply(&ident.to_string()),
+        (None, Member::Unnamed(index)) => index.index.to_string(),
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    if let Type::Path(p) = ty {
+        if let Some(seg) = p.path.segments.last() {
+            return seg.ident == "Option";
+        }
+    }
+    false
+}
+
+fn derives(ast: &DeriveInput, name: &str) -> bool {
+    ast.attrs
+        .iter()
+        .filter(|a| a.path.is_ident("derive"))
+        .filter_map(|a| {
+            a.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .any(|paths| {
+            paths
+                .iter()
+                .any(|p| p.segments.last().map(|s| s.ident == name).unwrap_or(false))
+        })
+}
+
+/// one limit inside `#[limit(...)]`
+enum Limit {
+    Len {
+        min: Option<LitInt>,
+        max: Option<LitInt>,
+    },
+    NonEmpty,
+    Range(Box<Expr>),
+    OneOf(Punctuated<Expr, Token![,]>),
+    Regex(LitStr),
+}
+
+impl Parse for Limit {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        match name.to_string().as_str() {
+            "len" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let (mut min, mut max) = (None, None);
+                let items = Punctuated::<LenBound, Token![,]>::parse_terminated(&content)?;
+                for LenBound(bound, value) in items {
+                    match bound.to_string().as_str() {
+                        "min" => min = Some(value),
+                        "max" => max = Some(value),
+                        _ => return Err(syn::Error::new_spanned(bound, "expected `min` or `max`")),
+                    }
+                }
+                if min.is_none() && max.is_none() {
+                    return Err(syn::Error::new_spanned(name, "len need `min` or `max`"));
+                }
+                Ok(Limit::Len { min, max })
+            }
+            "non_empty" => Ok(Limit::NonEmpty),
+            "range" => {
+                let content;
+                syn::parenthesized!(content in input);
+                Ok(Limit::Range(Box::new(content.parse()?)))
+            }
+            "one_of" => {
+                let content;
+                syn::parenthesized!(content in input);
+                Ok(Limit::OneOf(Punctuated::parse_terminated(&content)?))
+            }
+            "regex" => {
+                input.parse::<Token![=]>()?;
+                let re: LitStr = input.parse()?;
+                // a bad pattern fails here instead of panicking on the first check
+                #[cfg(feature = "regex")]
+                if let Err(e) = regex::Regex::new(&re.value()) {
+                    return Err(syn::Error::new_spanned(re, format!("invalid regex, {}", e)));
+                }
+                Ok(Limit::Regex(re))
+            }
+            _ => Err(syn::Error::new_spanned(
+                name,
+                "unknown limit, expected one of `len`, `non_empty`, `range`, `one_of`, `regex`",
+            )),
+        }
+    }
+}
+
+/// `min = 1` inside `len(...)`
+struct LenBound(Ident, LitInt);
+
+impl Parse for LenBound {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(LenBound(name, input.parse()?))
+    }
+}
+
+impl Limit {
+    /// call the check, the value is `__v`
+    fn check(&self, krate: &Path) -> quote::__private::TokenStream {
+        match self {
+            Limit::Len { min, max } => {
+                let min = option_token(min);
+                let max = option_token(max);
+                quote! {#krate::limit::len(__v, #min, #max)}
+            }
+            Limit::NonEmpty => quote! {#krate::limit::non_empty(__v)},
+            Limit::Range(range) => quote! {#krate::limit::range(__v, #range)},
+            Limit::OneOf(options) => {
+                let options = options.iter();
+                quote! {#krate::limit::one_of(__v, &[#(#options),*])}
+            }
+            Limit::Regex(re) => quote! {
+                {
+                    static __RE: ::std::sync::OnceLock<#krate::__private::regex::Regex> =
+                        ::std::sync::OnceLock::new();
+                    // checked by `chain_meta_limit`, can not fail
+                    let re = __RE.get_or_init(|| {
+                        #krate::__private::regex::Regex::new(#re).unwrap()
+                    });
+                    #krate::limit::regex(__v, re)
+                }
+            },
+        }
+    }
+}
+
+fn option_token(v: &Option<LitInt>) -> quote::__private::TokenStream {
+    match v {
+        Some(v) => quote! {::std::option::Option::Some(#v)},
+        None => quote! {::std::option::Option::None},
+    }
 }
@@ -1,235 +1,407 @@
-use std::iter::FromIterator;
-use syn::Ident;
+use chain_meta_attr::{
+    add_bounds, load_struct, load_values, load_variants, newtype_field, ContainerAttr,
+    FieldDefault, MetaField, MetaValue, MetaVariant, Style, VariantKind,
+};
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use syn::{Data, Generics};
-use syn::{DeriveInput, Type};
+use quote::quote;
+use syn::DeriveInput;
+use syn::{Data, Generics, Path};
 
 
-#[proc_macro_derive(LoadFormMap)]
+#[proc_macro_derive(LoadFormMap, attributes(meta))]
 pub fn msg_chain_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
+    let ast = syn::parse_macro_input!(input as DeriveInput);
 
     impl_from_chains_macro(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
-fn impl_from_chains_macro(ast: &DeriveInput) -> TokenStream {
+fn impl_from_chains_macro(ast: &DeriveInput) -> syn::Result<quote::__private::TokenStream> {
 
     let name = &ast.ident;
 
-    let generics = &ast.generics;
-    let (head_g, where_c) = load_generics(generics);
+    let container = ContainerAttr::from_attrs(&ast.attrs)?;
+    let krate = container.crate_path();
 
-    let data = &ast.data;
-    let (data, is_named) = load_data(data);
-    let data = data.unwrap();
-
-    let c_d = data.clone();
-    let create_data = c_d.iter().map(|f| {
-        let name = &f.0;
-        let map_name=&f.1;
-        let ty = &f.2;
-        let (t,b)=load_type(ty);
-        quote! {
-            let #name  :#t = #b::from_chain(map.get(stringify!(#map_name)))?;
+    let mut generics = ast.generics.clone();
+    let (load_body, type_eq_body, type_tags_body) = if let Data::Enum(en) = &ast.data {
+        let variants = load_variants(en, &container)?;
+        let wraps = variants.iter().filter_map(|v| match &v.kind {
+            VariantKind::Wrap(ty) => Some(ty),
+            _ => None,
+        });
+        add_bounds(&mut generics, wraps, &syn::parse_quote!(#krate::LoadFormMap));
+        for v in variants.iter() {
+            if let VariantKind::Inline(data) = &v.kind {
+                field_bounds(&mut generics, data, &krate);
+            }
         }
-    });
+        enum_bodys(&variants, &krate)
+    } else {
+        let (data, style) = load_struct(&ast.data, name, container.rule(), true)?;
+        field_bounds(&mut generics, &data, &krate);
+        let (create, new) = create_data(&data, style, &krate, quote! {Self});
+        let validate = if container.validate {
+            quote! {
+                let __res = #new;
+                #krate::ChainValidate::validate(&__res)?;
+                ::std::result::Result::Ok(__res)
+            }
+        } else {
+            quote! {
+                ::std::result::Result::Ok(
+                    #new
+                )
+            }
+        };
+        (
+            quote! {
+                if ! <Self as #krate::LoadFormMap>::can_match(map){
+                    return ::std::result::Result::Err(#krate::ChainError::unmatched(map))
+                }
+                #create
+                #validate
+            },
+            type_eq(&container.type_name(name), &container.type_alias),
+            type_tags(&container.type_name(name), &container.type_alias),
+        )
+    };
 
-    let set = data.iter().map(|f| &f.0);
+    // bounds of the `MessageChain` impl are needed by the supertrait
+    if generics.type_params().next().is_some() {
+        let (_, ty_g, _) = ast.generics.split_for_impl();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#name #ty_g: #krate::MessageChain));
+    }
+    let (impl_g, ty_g, where_c) = generics.split_for_impl();
 
-    let new = if is_named {
+    let deserialize = container.serde.then(|| {
+        let mut de_generics = generics.clone();
+        de_generics.params.insert(0, syn::parse_quote!('__de));
+        let (de_impl_g, _, _) = de_generics.split_for_impl();
         quote! {
-            Self{
-                #(#set),*
+            impl #de_impl_g #krate::__private::serde::Deserialize<'__de> for #name #ty_g #where_c {
+                fn deserialize<__D>(deserializer: __D) -> ::std::result::Result<Self, __D::Error>
+                where
+                    __D: #krate::__private::serde::Deserializer<'__de>,
+                {
+                    #krate::__private::deserialize_message(deserializer)
+                }
             }
         }
-    } else {
-        quote! {
-            Self
-        }
-    };
+    });
 
     let gen = quote! {
-        impl #head_g LoadFormMap  for #name #head_g #where_c {
-            fn load_from_map(map: &std::collections::HashMap<String, ChainMeta>) -> Option<Self> {
-                if ! Self::can_match(map){
-                    return None
-                }
-                #(#create_data)*
-                Some(
-                    #new
-                )
+        impl #impl_g #krate::LoadFormMap  for #name #ty_g #where_c {
+            fn try_load_from_map_with(
+                map: &::std::collections::HashMap<::std::string::String, #krate::ChainMeta>,
+                options: &#krate::LoadOptions,
+            ) -> ::std::result::Result<Self, #krate::ChainError> {
+                #load_body
             }
-            fn can_match(map:&std::collections::HashMap<String,ChainMeta>)->bool{
-                let __ty = map.get("type");
-                let s = String::from_chain(__ty);
-                match s{
-                    Some(s)=>
-                    {
-                        Self::type_eq(&s)
+            fn can_match(map:&::std::collections::HashMap<::std::string::String, #krate::ChainMeta>)->bool{
+                match map.get("type") {
+                    ::std::option::Option::Some(#krate::ChainMeta::Str(s)) => {
+                        <Self as #krate::LoadFormMap>::type_eq(s)
                     },
                     _=>false
                 }
             }
 
             fn type_eq(ty:&str)->bool{
-                ty == stringify!(#name) 
+                #type_eq_body
+            }
+
+            fn type_tags() -> ::std::vec::Vec<&'static str> {
+                #type_tags_body
             }
         }
+
+        #deserialize
     };
-    gen.into()
+    Ok(gen)
 }
 
-fn load_generics(g: &Generics) -> (quote::__private::TokenStream, quote::__private::TokenStream) {
-    //can set to where
-    let type_params = g.type_params();
-    let lifetimes = g.lifetimes();
-    //can not set to where
-    let const_params = g
-        .const_params()
-        .into_iter()
-        .map(|f| quote! {#f})
-        .collect::<Vec<_>>();
-
-    let where_clause = g.where_clause.clone();
-    let mut used = Vec::new();
-    let mut limits = Vec::new();
-    // load life time
-    for lifetime in lifetimes.into_iter() {
-        let life = &lifetime.lifetime;
-        let has_limit = &lifetime.bounds.len() > &0;
-        used.push(quote! {#life});
-
-        let t = if has_limit {
-            quote! {
-                #lifetime
-            }
-        } else {
-            quote! {}
-        };
-        limits.push(t);
-    }
-    //load type params
-    for type_param in type_params.into_iter() {
-        let base = &type_param.ident;
-        let has_limits = type_param.bounds.len() > 0;
-        used.push(quote! {#base});
-        let t = if has_limits {
-            quote! {
-                #type_param
-            }
-        } else {
-            quote! {}
-        };
-        limits.push(t);
-    }
+#[proc_macro_derive(FromChainMeta, attributes(meta))]
+pub fn from_chain_meta_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
 
-    let mut where_limit = Vec::new();
-    // genreate where
-    if let Some(where_clause) = where_clause {
-        for wh in where_clause.predicates {
-            where_limit.push(quote! {
-                #wh
-            })
-        }
-    }
+    impl_from_chain_meta(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
 
-    for limit in limits {
-        where_limit.push(limit);
-    }
-    let where_limit = where_limit.iter();
-    let sub_where = if where_limit.len() > 0 {
+/// newtype is loaded from the same value as the wrapped one, struct from a map without `type`,
+/// enum with only unit variants from a string
+fn impl_from_chain_meta(ast: &DeriveInput) -> syn::Result<quote::__private::TokenStream> {
+    let name = &ast.ident;
+    let container = ContainerAttr::from_attrs(&ast.attrs)?;
+    let krate = container.crate_path();
+
+    let mut generics = ast.generics.clone();
+    let load_body = if let Some(inner) = newtype_field(&ast.data) {
+        add_bounds(&mut generics, Some(inner), &syn::parse_quote!(#krate::FromChainMeta));
+        let validate = container.validate.then(|| {
+            quote! {#krate::ChainValidate::validate(&__res)?;}
+        });
         quote! {
-            where
-            #( #where_limit),*
+            let __res = Self(<#inner as #krate::FromChainMeta>::try_from_chain_with(chain, options)?);
+            #validate
+            ::std::result::Result::Ok(__res)
+        }
+    } else if let Data::Enum(en) = &ast.data {
+        let values = load_values(en, &container)?;
+        let expected = values
+            .iter()
+            .map(|v| format!("`{}`", v.value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let arms = values.iter().map(|MetaValue { ident, value, alias }| {
+            quote! {#value #(| #alias)* => ::std::result::Result::Ok(Self::#ident)}
+        });
+        quote! {
+            let _ = options;
+            let __value = <::std::string::String as #krate::FromChainMeta>::try_from_chain(chain)?;
+            match __value.as_str() {
+                #(#arms,)*
+                _ => ::std::result::Result::Err(#krate::ChainError::invalid(::std::format!(
+                    "unknown value `{}`, expected one of {}",
+                    __value,
+                    #expected
+                ))),
+            }
         }
     } else {
-        quote! {}
-    };
-
-    let g_useds = used.iter();
-    // use gereric
-    let g = quote! {
-        <
-            # ( #g_useds ),*
-            #( #const_params),*
-        >
+        let (data, style) = load_struct(&ast.data, name, container.rule(), false)?;
+        field_bounds(&mut generics, &data, &krate);
+        let (create, new) = create_data(&data, style, &krate, quote! {Self});
+        let validate = container.validate.then(|| {
+            quote! {#krate::ChainValidate::validate(&__res)?;}
+        });
+        quote! {
+            let map = &*#krate::__private::struct_fields(chain)?;
+            #create
+            let __res = #new;
+            #validate
+            ::std::result::Result::Ok(__res)
+        }
     };
+    let (impl_g, ty_g, where_c) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_g #krate::FromChainMeta for #name #ty_g #where_c {
+            fn try_from_chain(
+                chain: ::std::option::Option<&#krate::ChainMeta>,
+            ) -> ::std::result::Result<Self, #krate::ChainError> {
+                <Self as #krate::FromChainMeta>::try_from_chain_with(
+                    chain,
+                    &::std::default::Default::default(),
+                )
+            }
+            fn try_from_chain_with(
+                chain: ::std::option::Option<&#krate::ChainMeta>,
+                options: &#krate::LoadOptions,
+            ) -> ::std::result::Result<Self, #krate::ChainError> {
+                #load_body
+            }
+        }
 
-    (g, sub_where)
-}
+        impl #impl_g ::std::convert::TryFrom<#krate::ChainMeta> for #name #ty_g #where_c {
+            type Error = #krate::ChainError;
 
-fn load_data(data: &Data) -> (Option<Vec<(syn::Ident,Ident, Type)>>, bool) {
-    if let Data::Struct(st) = data {
-        let fields = &st.fields;
-        match fields {
-            syn::Fields::Named(ns) => {
-                let fields = &ns.named;
-                let res = fields
-                    .into_iter()
-                    .map(|f| (&f.ident, &f.ty))
-                    .filter(|predicate| if let None = predicate.0 { false } else { true })
-                    .map(|f| (f.0.clone().unwrap(),transfrom_name(f.0.clone().unwrap().to_string()), f.1.clone()))
-                    
-                    .collect::<Vec<_>>();
-                (Some(res), true)
-            }
-            syn::Fields::Unnamed(_) => (None, false),
-            syn::Fields::Unit => (Some(vec![]), false),
+            fn try_from(
+                value: #krate::ChainMeta,
+            ) -> ::std::result::Result<Self, #krate::ChainError> {
+                <Self as #krate::FromChainMeta>::try_from_chain(::std::option::Option::Some(&value))
+            }
         }
-    } else {
-        (None, false)
-    }
+    })
 }
 
+/// load all fields from `map`, and how to build `new` with them
+fn create_data(
+    data: &[MetaField],
+    style: Style,
+    krate: &Path,
+    new: quote::__private::TokenStream,
+) -> (quote::__private::TokenStream, quote::__private::TokenStream) {
+    let create_data = data.iter().map(|f| {
+        let name = &f.ident;
+        let t = &f.ty;
+        let default = f.attr.default.as_ref().map(|d| match d {
+            FieldDefault::Trait => quote! {::std::default::Default::default()},
+            FieldDefault::Path(p) => quote! {#p()},
+        });
 
-fn load_type(ty:&Type)->(quote::__private::TokenStream,quote::__private::TokenStream){
-    let ty_def=quote! {#ty};
-    if let Type::Path(p) = ty {
-        let path=&p.path;
-        let seg=&path.segments;
-        let mut paths=Vec::new();
+        if f.attr.skip {
+            let default = default.unwrap_or_else(|| quote! {::std::default::Default::default()});
+            return quote! {
+                let #name :#t = #default;
+            };
+        }
 
-        for seg in seg.into_iter(){
-            let ident=&seg.ident;
-            paths.push(ident);
+        let map_name = &f.key;
+        let alias = f.attr.alias.iter();
+        let numeric_strings = if f.attr.numeric_strings {
+            Some(quote! {.numeric_strings(true)})
+        } else {
+            None
+        };
+        let lenient = f.attr.lenient.map(|l| quote! {.lenient(#l)});
+        let options = if numeric_strings.is_some() || lenient.is_some() {
+            quote! {&options #numeric_strings #lenient}
+        } else {
+            quote! {options}
+        };
+        let load = quote! {
+            <#t as #krate::FromChainMeta>::try_from_chain_with(__value, #options).map_err(|e| e.at_key(__key))?
+        };
+        let load = match default {
+            Some(default) => quote! {
+                match __value {
+                    ::std::option::Option::None => #default,
+                    __value => #load,
+                }
+            },
+            None => load,
+        };
+        quote! {
+            let #name :#t = {
+                let (__key, __value) = (#map_name, map.get(#map_name));
+                #(
+                    let (__key, __value) = match __value {
+                        ::std::option::Option::None => match map.get(#alias) {
+                            ::std::option::Option::Some(v) => (#alias, ::std::option::Option::Some(v)),
+                            ::std::option::Option::None => (__key, ::std::option::Option::None),
+                        },
+                        v => (__key, v),
+                    };
+                )*
+                #load
+            };
         }
+    });
 
-        let paths=paths.iter();
-        let base=quote! {#(#paths)::*};
-        (ty_def,base)
-    }else{
-        (
-            quote! {#ty},
-            quote! {#ty}
-        )
-    }   
+    let set = data.iter().map(|f| &f.ident);
+
+    let new = match style {
+        Style::Named => quote! {
+            #new{
+                #(#set),*
+            }
+        },
+        Style::Tuple => quote! {
+            #new(
+                #(#set),*
+            )
+        },
+        Style::Unit => new,
+    };
+
+    (quote! { #(#create_data)* }, new)
+}
+
+/// `ty` is `tag` or any of `alias`
+fn type_eq(tag: &str, alias: &[String]) -> quote::__private::TokenStream {
+    quote! {
+        ty == #tag #( || ty == #alias)*
+    }
+}
+
+/// `tag` and all `alias`
+fn type_tags(tag: &str, alias: &[String]) -> quote::__private::TokenStream {
+    quote! {
+        ::std::vec![#tag #(, #alias)*]
+    }
 }
 
-fn transfrom_name(name:String)->Ident{
-    name.split("_")
-    .into_iter()
-    .map(|f|f.chars())
-    .map(|mut f|{
-        let mut s=String::new();
-        let first=f.next();
-        let left_str=String::from_iter(f);
-        if let Some(ch)=first{
-            s.push_str(&ch.to_uppercase().to_string());
-            s.push_str(&left_str);
+fn enum_bodys(
+    variants: &[MetaVariant],
+    krate: &Path,
+) -> (
+    quote::__private::TokenStream,
+    quote::__private::TokenStream,
+    quote::__private::TokenStream,
+) {
+    let mut loads = Vec::new();
+    let mut type_eqs = Vec::new();
+    let mut tags = Vec::new();
+    let mut wrap_tys = Vec::new();
+    for MetaVariant { ident: name, kind, tag, type_alias } in variants {
+        let tag_eq = type_eq(tag, type_alias);
+        match kind {
+            VariantKind::Wrap(ty) => {
+                loads.push(quote! {
+                    if <#ty as #krate::LoadFormMap>::can_match(map) {
+                        return ::std::result::Result::Ok(Self::#name(
+                            <#ty as #krate::LoadFormMap>::try_load_from_map_with(map, options)?
+                        ));
+                    }
+                });
+                type_eqs.push(quote! {<#ty as #krate::LoadFormMap>::type_eq(ty)});
+                wrap_tys.push(ty);
+            }
+            VariantKind::Inline(data) => {
+                let (create, new) = create_data(data, Style::Named, krate, quote! {Self::#name});
+                loads.push(quote! {
+                    if { let ty = __ty.as_str(); #tag_eq } {
+                        #create
+                        return ::std::result::Result::Ok(#new);
+                    }
+                });
+                type_eqs.push(quote! {(#tag_eq)});
+                tags.push(type_tags(tag, type_alias));
+            }
+            VariantKind::Unit => {
+                loads.push(quote! {
+                    if { let ty = __ty.as_str(); #tag_eq } {
+                        return ::std::result::Result::Ok(Self::#name);
+                    }
+                });
+                type_eqs.push(quote! {(#tag_eq)});
+                tags.push(type_tags(tag, type_alias));
+            }
         }
-        s
-    })
-    .reduce(|mut f,b|{f.push_str(&b);f})
+    }
 
-    .and_then(|f|{
-        let mut ch_iter=f.chars();
-        let first=ch_iter.next().unwrap();
-        let left=String::from_iter(ch_iter);
-        let mut t=String::from(first.to_lowercase().to_string());
-        t.push_str(&left);
+    (
+        quote! {
+            let __ty = <::std::string::String as #krate::FromChainMeta>::try_from_chain(map.get("type"))
+                .map_err(|e| e.at_key("type"))?;
+            #(#loads)*
+            ::std::result::Result::Err(#krate::ChainError::unknown_type(__ty))
+        },
+        quote! {
+            false #( || #type_eqs)*
+        },
+        // a wrapped type without known tags make the whole enum unknown
+        quote! {
+            let mut __tags: ::std::vec::Vec<&'static str> = ::std::vec::Vec::new();
+            #( __tags.extend(#tags); )*
+            #(
+                let __inner = <#wrap_tys as #krate::LoadFormMap>::type_tags();
+                if __inner.is_empty() {
+                    return ::std::vec::Vec::new();
+                }
+                __tags.extend(__inner);
+            )*
+            __tags
+        },
+    )
+}
 
-        Some(t)
-    })
-    .and_then(|f|Some(format_ident!("{}",f))).unwrap()
+/// loaded fields need `FromChainMeta`, filled by `Default::default()` need `Default`
+fn field_bounds(generics: &mut Generics, data: &[MetaField], krate: &Path) {
+    let loaded = data.iter().filter(|f| !f.attr.skip).map(|f| &f.ty);
+    add_bounds(generics, loaded, &syn::parse_quote!(#krate::FromChainMeta));
+    let defaults = data
+        .iter()
+        .filter(|f| {
+            matches!(f.attr.default, Some(FieldDefault::Trait))
+                || (f.attr.skip && f.attr.default.is_none())
+        })
+        .map(|f| &f.ty);
+    add_bounds(generics, defaults, &syn::parse_quote!(::std::default::Default));
 }
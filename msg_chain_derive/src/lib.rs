@@ -1,200 +1,247 @@
-use std::iter::FromIterator;
-use std::vec;
-
+use chain_meta_attr::{
+    add_bounds, load_struct, load_values, load_variants, newtype_field, ContainerAttr, MetaField,
+    MetaValue, MetaVariant, VariantKind,
+};
 use proc_macro::TokenStream;
 
 use quote::quote;
-use quote::format_ident;
-use syn::{Data, Ident};
-use syn::{DeriveInput, Generics};
+use syn::DeriveInput;
+use syn::{Data, Path, Type};
 
 #[proc_macro_derive(MessageChain,attributes(meta))]
 pub fn msg_chain_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
+    let ast = syn::parse_macro_input!(input as DeriveInput);
 
     impl_msg_chains_macro(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
-fn impl_msg_chains_macro(ast: &DeriveInput) -> TokenStream {
+fn impl_msg_chains_macro(ast: &DeriveInput) -> syn::Result<quote::__private::TokenStream> {
     //type get
     let chain_type = &ast.ident;
 
-    //type used gerace
-    let generics = &ast.generics;
-    let (impl_g, where_c) = load_generics(generics);
+    let container = ContainerAttr::from_attrs(&ast.attrs)?;
+    let krate = container.crate_path();
 
     //load inside items
     let items = &ast.data;
-    let datas = load_data(items).unwrap();
-    let datas = datas.iter();
-    let match_data = datas.clone().map(|f| {
-        let a=&f.0;
-        let b=&f.1;
-        quote! {
-            stringify!(#b)=>Some(self.#a.into_chain())
-        }
-    });
+    let mut generics = ast.generics.clone();
+    let (type_body, get_body, keys_body) = if let Data::Enum(en) = items {
+        let variants = load_variants(en, &container)?;
+        let wraps = variants.iter().filter_map(|v| match &v.kind {
+            VariantKind::Wrap(ty) => Some(ty),
+            _ => None,
+        });
+        add_bounds(&mut generics, wraps, &syn::parse_quote!(#krate::MessageChain));
+        let fields = variants.iter().flat_map(|v| match &v.kind {
+            VariantKind::Inline(datas) => field_types(datas),
+            _ => Vec::new(),
+        });
+        add_bounds(&mut generics, fields, &syn::parse_quote!(#krate::IntoChainMeta));
+        enum_bodys(&variants, &krate)
+    } else {
+        let (datas, _) = load_struct(items, chain_type, container.rule(), true)?;
+        add_bounds(&mut generics, field_types(&datas), &syn::parse_quote!(#krate::IntoChainMeta));
+        let get_body = match_key(&datas, &krate, |f| {
+            let member = &f.member;
+            quote! {&self.#member}
+        });
+        let all_key_name = datas.iter().filter(|f| !f.attr.skip).map(|f| &f.key);
+        let type_name = container.type_name(chain_type);
+        (
+            quote! {#type_name},
+            get_body,
+            quote! {
+                ::std::vec![
+                    # ( #all_key_name),*
+                ]
+            },
+        )
+    };
+    let (impl_g, ty_g, where_c) = generics.split_for_impl();
 
-    let match_data=if match_data.len() > 0{
+    let serialize = container.serde.then(|| {
         quote! {
-            match key {
-                #( #match_data ),*
-                ,
-                _=>None
+            impl #impl_g #krate::__private::serde::Serialize for #chain_type #ty_g #where_c {
+                fn serialize<__S>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: #krate::__private::serde::Serializer,
+                {
+                    #krate::__private::serialize_message(self, serializer)
+                }
             }
         }
-    }else{
-        quote! {
-            None
-        }
-    };
-
-    let all_key_name=datas.map(|f|&f.1);
+    });
 
     let gen = quote! {
 
-        impl #impl_g MessageChain for #chain_type #impl_g #where_c {
-            fn get_type(&self) -> &'static str{
-                stringify!(#chain_type)
+        impl #impl_g #krate::MessageChain for #chain_type #ty_g #where_c {
+            fn get_type(&self) -> &str{
+                #type_body
             }
-            fn get(&self, key: &str) -> Option<ChainMeta> {
-                #match_data
+            fn get(&self, key: &str) -> ::std::option::Option<#krate::ChainMeta> {
+                #get_body
             }
-            fn all_keys(&self) -> Vec<&'static str> {
-                vec![
-                    # ( stringify!(#all_key_name)),*
-                ]
+            fn all_keys(&self) -> ::std::vec::Vec<&str> {
+                #keys_body
             }
         }
+
+        #serialize
     };
-    gen.into()
+    Ok(gen)
+}
+
+#[proc_macro_derive(IntoChainMeta, attributes(meta))]
+pub fn into_chain_meta_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+
+    impl_into_chain_meta(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
-fn load_generics(g: &Generics) -> (quote::__private::TokenStream, quote::__private::TokenStream) {
-    //can set to where
-    let type_params = g.type_params();
-    let lifetimes = g.lifetimes();
-    //can not set to where
-    let const_params = g
-        .const_params()
-        .into_iter()
-        .map(|f| quote! {#f})
-        .collect::<Vec<_>>();
-
-    let where_clause = g.where_clause.clone();
-    let mut used = Vec::new();
-    let mut limits = Vec::new();
-    // load life time
-    for lifetime in lifetimes.into_iter() {
-        let life = &lifetime.lifetime;
-        let has_limit = &lifetime.bounds.len() > &0;
-        used.push(quote! {#life});
-
-        let t = if has_limit {
+/// newtype is the same as the wrapped value, struct become a map without `type`,
+/// enum with only unit variants become a string
+fn impl_into_chain_meta(ast: &DeriveInput) -> syn::Result<quote::__private::TokenStream> {
+    let name = &ast.ident;
+    let container = ContainerAttr::from_attrs(&ast.attrs)?;
+    let krate = container.crate_path();
+
+    let mut generics = ast.generics.clone();
+    let (into_body, field_fn) = if let Some(inner) = newtype_field(&ast.data) {
+        add_bounds(&mut generics, Some(inner), &syn::parse_quote!(#krate::IntoChainMeta));
+        (
+            quote! {<#inner as #krate::IntoChainMeta>::into_chain(&self.0)},
+            // `Field::Missing` inside newtype still omit the key
+            Some(quote! {
+                fn into_chain_field(&self) -> ::std::option::Option<#krate::ChainMeta> {
+                    <#inner as #krate::IntoChainMeta>::into_chain_field(&self.0)
+                }
+            }),
+        )
+    } else if let Data::Enum(en) = &ast.data {
+        let values = load_values(en, &container)?;
+        let arms = values.iter().map(|MetaValue { ident, value, .. }| {
+            quote! {Self::#ident => #krate::ChainMeta::Str(::std::string::String::from(#value))}
+        });
+        (
+            quote! {match *self { #(#arms),* }},
+            None,
+        )
+    } else {
+        let (datas, _) = load_struct(&ast.data, name, container.rule(), false)?;
+        add_bounds(&mut generics, field_types(&datas), &syn::parse_quote!(#krate::IntoChainMeta));
+        let inserts = datas.iter().filter(|f| !f.attr.skip).map(|f| {
+            let (ty, member, key) = (&f.ty, &f.member, &f.key);
             quote! {
-                #lifetime
+                if let ::std::option::Option::Some(__v) =
+                    <#ty as #krate::IntoChainMeta>::into_chain_field(&self.#member)
+                {
+                    __map.insert(::std::borrow::Cow::Borrowed(#key), __v);
+                }
             }
-        } else {
-            quote! {}
-        };
-        limits.push(t);
-    }
-    //load type params
-    for type_param in type_params.into_iter() {
-        let base = &type_param.ident;
-        let has_limits = type_param.bounds.len() > 0;
-        used.push(quote! {#base});
-        let t = if has_limits {
+        });
+        (
             quote! {
-                #type_param
+                let mut __map = #krate::IndexMap::new();
+                #(#inserts)*
+                #krate::ChainMeta::Map(__map)
+            },
+            None,
+        )
+    };
+    let (impl_g, ty_g, where_c) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_g #krate::IntoChainMeta for #name #ty_g #where_c {
+            fn into_chain(&self) -> #krate::ChainMeta {
+                #into_body
             }
-        } else {
-            quote! {}
-        };
-        limits.push(t);
-    }
-
-    let mut where_limit = Vec::new();
-    // genreate where
-    if let Some(where_clause) = where_clause {
-        for wh in where_clause.predicates {
-            where_limit.push(quote! {
-                #wh
-            })
+            #field_fn
         }
-    }
 
-    for limit in limits {
-        where_limit.push(limit);
-    }
-    let where_limit = where_limit.iter();
-    let sub_where = if where_limit.len()>0{ quote! {
-        where
-        #( #where_limit),*
-    }}else { quote! {}};
-
-    let g_useds = used.iter();
-    // use gereric
-    let g = quote! {
-        <
-            # ( #g_useds ),*
-            #( #const_params),*
-        >
-    };
-
-    (g, sub_where)
+        impl #impl_g ::std::convert::From<#name #ty_g> for #krate::ChainMeta #where_c {
+            fn from(value: #name #ty_g) -> Self {
+                <#name #ty_g as #krate::IntoChainMeta>::into_chain(&value)
+            }
+        }
+    })
 }
 
-fn load_data(data: &Data) -> Option<Vec<(syn::Ident,syn::Ident)>> {
-    if let Data::Struct(st) = data {
-        let fields = &st.fields;
-        match fields {
-            syn::Fields::Named(ns) => {
-                let fields = &ns.named;
-                let res = fields
-                    .into_iter()
-                    .map(|f| &f.ident)
-                    .filter(|predicate| if let None = predicate { false } else { true })
-                    .map(|f| f.clone().unwrap())
-                    .map(|f|(f.clone(),transfrom_name(f.to_string())))
-
-                    .collect::<Vec<_>>();
-                Some(res)
+/// `match key` on all fields, `access` give a reference to the field
+fn match_key<F>(datas: &[MetaField], krate: &Path, access: F) -> quote::__private::TokenStream
+where
+    F: Fn(&MetaField) -> quote::__private::TokenStream,
+{
+    let match_data = datas.iter().filter(|f| !f.attr.skip).map(|f| {
+        let a = access(f);
+        let b = &f.key;
+        let ty = &f.ty;
+        quote! {
+            #b=><#ty as #krate::IntoChainMeta>::into_chain_field(#a)
+        }
+    });
+
+    if match_data.clone().count() > 0 {
+        quote! {
+            match key {
+                #( #match_data ),*
+                ,
+                _=>::std::option::Option::None
             }
-            syn::Fields::Unnamed(_) => None,
-            syn::Fields::Unit => Some(vec![]),
         }
     } else {
-        None
+        quote! {
+            ::std::option::Option::None
+        }
     }
 }
 
-fn transfrom_name(name:String)->Ident{
-    name.split("_")
-    .into_iter()
-    .map(|f|f.chars())
-    .map(|mut f|{
-        let mut s=String::new();
-        let first=f.next();
-        let left_str=String::from_iter(f);
-        if let Some(ch)=first{
-            s.push_str(&ch.to_uppercase().to_string());
-            s.push_str(&left_str);
+fn enum_bodys(
+    variants: &[MetaVariant],
+    krate: &Path,
+) -> (
+    quote::__private::TokenStream,
+    quote::__private::TokenStream,
+    quote::__private::TokenStream,
+) {
+    let mut types = Vec::new();
+    let mut gets = Vec::new();
+    let mut keys = Vec::new();
+    for MetaVariant { ident: name, kind, tag, .. } in variants {
+        match kind {
+            VariantKind::Wrap(ty) => {
+                types.push(quote! {Self::#name(v) => <#ty as #krate::MessageChain>::get_type(v)});
+                gets.push(quote! {Self::#name(v) => <#ty as #krate::MessageChain>::get(v, key)});
+                keys.push(quote! {Self::#name(v) => <#ty as #krate::MessageChain>::all_keys(v)});
+            }
+            VariantKind::Inline(datas) => {
+                let fields = datas.iter().filter(|f| !f.attr.skip).map(|f| &f.ident);
+                let get_body = match_key(datas, krate, |f| {
+                    let ident = &f.ident;
+                    quote! {#ident}
+                });
+                let all_key_name = datas.iter().filter(|f| !f.attr.skip).map(|f| &f.key);
+                types.push(quote! {Self::#name{..} => #tag});
+                gets.push(quote! {Self::#name{ #(#fields,)* .. } => #get_body});
+                keys.push(quote! {Self::#name{..} => ::std::vec![ # ( #all_key_name),* ]});
+            }
+            VariantKind::Unit => {
+                types.push(quote! {Self::#name => #tag});
+                gets.push(quote! {Self::#name => ::std::option::Option::None});
+                keys.push(quote! {Self::#name => ::std::vec![]});
+            }
         }
-        s
-    })
-    .reduce(|mut f,b|{f.push_str(&b);f})
-
-    .and_then(|f|{
-        let mut ch_iter=f.chars();
-        let first=ch_iter.next().unwrap();
-        let left=String::from_iter(ch_iter);
-        let mut t=String::from(first.to_lowercase().to_string());
-        t.push_str(&left);
-
-        Some(t)
-    })
-    .and_then(|f|Some(format_ident!("{}",f))).unwrap()
+    }
+    (
+        quote! { match self { #(#types),* } },
+        quote! { match self { #(#gets),* } },
+        quote! { match self { #(#keys),* } },
+    )
 }
 
-
+/// types of fields that take part in the chain
+fn field_types(datas: &[MetaField]) -> Vec<&Type> {
+    datas.iter().filter(|f| !f.attr.skip).map(|f| &f.ty).collect()
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{ChainMeta, Number};
+
+/// one step of the path to the failed field
+#[derive(Debug, PartialEq, Clone)]
+pub enum PathSeg {
+    Key(String),
+    Index(usize),
+}
+
+/// the reason why a chain can not be loaded
+#[derive(Debug, PartialEq, Clone)]
+pub enum ErrorKind {
+    /// required key not exist
+    MissingField,
+    /// the ChainMeta variant is not the expect one
+    TypeMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// number not fit in target type
+    Overflow { target: &'static str, value: Number },
+    /// the `type` of chain not supported
+    UnknownType(String),
+    /// value break the limit of field
+    Invalid(String),
+}
+
+/// Chain Error:
+///  why and where loading a chain failed
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChainError {
+    kind: ErrorKind,
+    path: Vec<PathSeg>,
+}
+
+impl ChainError {
+    pub fn new(kind: ErrorKind) -> Self {
+        Self { kind, path: vec![] }
+    }
+
+    pub fn missing() -> Self {
+        Self::new(ErrorKind::MissingField)
+    }
+
+    pub fn mismatch(expected: &'static str, actual: &ChainMeta) -> Self {
+        Self::new(ErrorKind::TypeMismatch {
+            expected,
+            actual: actual.variant_name(),
+        })
+    }
+
+    pub fn overflow(target: &'static str, value: Number) -> Self {
+        Self::new(ErrorKind::Overflow { target, value })
+    }
+
+    pub fn unknown_type<S: Into<String>>(ty: S) -> Self {
+        Self::new(ErrorKind::UnknownType(ty.into()))
+    }
+
+    pub fn invalid<S: Into<String>>(reason: S) -> Self {
+        Self::new(ErrorKind::Invalid(reason.into()))
+    }
+
+    /// error for a map that no loader can match
+    pub fn unmatched(map: &HashMap<String, ChainMeta>) -> Self {
+        match map.get("type") {
+            Some(ChainMeta::Str(ty)) => Self::unknown_type(ty.as_str()),
+            Some(other) => Self::mismatch("Str", other).at_key("type"),
+            None => Self::missing().at_key("type"),
+        }
+    }
+
+    /// mark the error happen inside `key`
+    pub fn at_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.path.insert(0, PathSeg::Key(key.into()));
+        self
+    }
+
+    /// mark the error happen inside element `index`
+    pub fn at_index(mut self, index: usize) -> Self {
+        self.path.insert(0, PathSeg::Index(index));
+        self
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn path(&self) -> &[PathSeg] {
+        &self.path
+    }
+
+    /// path like `nodeList[0].senderId`
+    pub fn path_string(&self) -> String {
+        let mut s = String::new();
+        for seg in &self.path {
+            match seg {
+                PathSeg::Key(k) => {
+                    if !s.is_empty() {
+                        s.push('.');
+                    }
+                    s.push_str(k);
+                }
+                PathSeg::Index(i) => s.push_str(&format!("[{}]", i)),
+            }
+        }
+        s
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::MissingField => write!(f, "missing field"),
+            ErrorKind::TypeMismatch { expected, actual } => {
+                write!(f, "expected {}, found {}", expected, actual)
+            }
+            ErrorKind::Overflow { target, value } => {
+                write!(f, "number {:?} out of range for {}", value, target)
+            }
+            ErrorKind::UnknownType(ty) => write!(f, "unsupported message type `{}`", ty),
+            ErrorKind::Invalid(reason) => write!(f, "invalid value, {}", reason),
+        }
+    }
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "`{}`: {}", self.path_string(), self.kind)
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
@@ -0,0 +1,81 @@
+use crate::{ChainError, ChainMeta, FromChainMeta, IntoChainMeta, LoadOptions};
+
+/// Field:
+///  a value that tells a missing key apart from an explicit `null`,
+///  `Missing` is omitted by `get_all` and `map_generate!`, `Null` is kept
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub enum Field<T> {
+    /// the key not exist
+    #[default]
+    Missing,
+    /// the key exist with `null`
+    Null,
+    Value(T),
+}
+
+impl<T> Field<T> {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Field::Missing)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Field::Null)
+    }
+
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Field::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// both `Missing` and `Null` become `None`
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Field::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Field<T> {
+    fn from(v: Option<T>) -> Self {
+        match v {
+            Some(v) => Field::Value(v),
+            None => Field::Null,
+        }
+    }
+}
+
+impl<T: IntoChainMeta> IntoChainMeta for Field<T> {
+    fn into_chain(&self) -> ChainMeta {
+        match self {
+            Field::Value(v) => v.into_chain(),
+            _ => ChainMeta::Null,
+        }
+    }
+
+    fn into_chain_field(&self) -> Option<ChainMeta> {
+        match self {
+            Field::Missing => None,
+            _ => Some(self.into_chain()),
+        }
+    }
+}
+
+impl<T: FromChainMeta> FromChainMeta for Field<T> {
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        Self::try_from_chain_with(chain, &LoadOptions::default())
+    }
+
+    fn try_from_chain_with(
+        chain: Option<&ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Self, ChainError> {
+        match chain {
+            None => Ok(Field::Missing),
+            Some(ChainMeta::Null) => Ok(Field::Null),
+            chain => T::try_from_chain_with(chain, options).map(Field::Value),
+        }
+    }
+}
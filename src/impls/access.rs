@@ -0,0 +1,121 @@
+use std::ops::Index;
+
+use crate::{ChainMap, ChainMeta, Number};
+
+static NULL: ChainMeta = ChainMeta::Null;
+
+impl ChainMeta {
+    pub fn is_null(&self) -> bool {
+        matches!(self, ChainMeta::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ChainMeta::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ChainMeta::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// `Some` if the number fit in `u64`, floats are `None`
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            ChainMeta::Num(Number::N(n)) => Some(*n),
+            ChainMeta::Num(Number::T(n)) if *n >= 0 => Some(*n as u64),
+            _ => None,
+        }
+    }
+
+    /// `Some` if the number fit in `i64`, floats are `None`
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ChainMeta::Num(Number::N(n)) if *n <= i64::MAX as u64 => Some(*n as i64),
+            ChainMeta::Num(Number::T(n)) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// any number, integers may lose precision
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ChainMeta::Num(Number::N(n)) => Some(*n as f64),
+            ChainMeta::Num(Number::T(n)) => Some(*n as f64),
+            ChainMeta::Num(Number::Float(f)) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<ChainMeta>> {
+        match self {
+            ChainMeta::SubChains(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&ChainMap> {
+        match self {
+            ChainMeta::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// `meta.get("key")` for `Map`, `meta.get(0)` for `SubChains`,
+    /// `None` if the key or index not exist, or the variant can not be indexed
+    pub fn get<I: ChainIndex>(&self, index: I) -> Option<&ChainMeta> {
+        index.index_into(self)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T: Sealed + ?Sized> Sealed for &T {}
+}
+
+/// Chain Index:
+///  types can index into `ChainMeta`, string for `Map` and `usize` for `SubChains`
+pub trait ChainIndex: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v>(&self, meta: &'v ChainMeta) -> Option<&'v ChainMeta>;
+}
+
+impl ChainIndex for usize {
+    fn index_into<'v>(&self, meta: &'v ChainMeta) -> Option<&'v ChainMeta> {
+        meta.as_array()?.get(*self)
+    }
+}
+
+impl ChainIndex for str {
+    fn index_into<'v>(&self, meta: &'v ChainMeta) -> Option<&'v ChainMeta> {
+        meta.as_object()?.get(self)
+    }
+}
+
+impl ChainIndex for String {
+    fn index_into<'v>(&self, meta: &'v ChainMeta) -> Option<&'v ChainMeta> {
+        self.as_str().index_into(meta)
+    }
+}
+
+impl<T: ChainIndex + ?Sized> ChainIndex for &T {
+    fn index_into<'v>(&self, meta: &'v ChainMeta) -> Option<&'v ChainMeta> {
+        (**self).index_into(meta)
+    }
+}
+
+/// same as `get`, but `Null` instead of `None`, so `meta["sender"]["id"]` never panic
+impl<I: ChainIndex> Index<I> for ChainMeta {
+    type Output = ChainMeta;
+
+    fn index(&self, index: I) -> &ChainMeta {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
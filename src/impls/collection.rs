@@ -1,6 +1,13 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::convert::TryInto;
+use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::Arc;
 
-use crate::{ChainMeta, FromChainMeta, IntoChainMeta};
+use crate::{ChainError, ChainMap, ChainMeta, FromChainMeta, IndexMap, IntoChainMeta, LoadOptions};
+
+use super::require;
 
 impl IntoChainMeta for String {
     fn into_chain(&self) -> ChainMeta {
@@ -9,11 +16,10 @@ impl IntoChainMeta for String {
 }
 
 impl FromChainMeta for String {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Str(s) = chain? {
-            Some(s.clone())
-        } else {
-            None
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        match require(chain)? {
+            ChainMeta::Str(s) => Ok(s.clone()),
+            other => Err(ChainError::mismatch("Str", other)),
         }
     }
 }
@@ -24,82 +30,316 @@ impl IntoChainMeta for str {
     }
 }
 
+impl IntoChainMeta for Cow<'_, str> {
+    fn into_chain(&self) -> ChainMeta {
+        ChainMeta::Str(self.to_string())
+    }
+}
 
+impl FromChainMeta for Cow<'_, str> {
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        String::try_from_chain(chain).map(Cow::Owned)
+    }
+}
 
-impl<T> IntoChainMeta for Vec<T>
-where
-    T: IntoChainMeta,
-{
+impl IntoChainMeta for char {
     fn into_chain(&self) -> ChainMeta {
-        ChainMeta::SubChains(self.iter().map(|t| t.into_chain()).collect())
+        ChainMeta::Str(self.to_string())
     }
 }
 
-impl<T: FromChainMeta> FromChainMeta for Vec<T> {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::SubChains(v) = chain? {
-            let v = v
-                .iter()
-                .map(|f| T::from_chain(Some(f)))
-                .filter(|f| match f {
-                    Some(_) => true,
-                    None => false,
-                })
-                .map(|f| f.unwrap())
-                .collect::<Vec<T>>();
-            Some(v)
-        } else {
-            None
+impl FromChainMeta for char {
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        let s = String::try_from_chain(chain)?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(ChainError::invalid(format!("{:?} is not a single char", s))),
         }
     }
 }
 
-impl<T: IntoChainMeta> IntoChainMeta for HashMap<&'static str, T> {
+macro_rules! pointer_impl {
+    ($($p:ident),*) => {
+        $(
+            impl<T: IntoChainMeta + ?Sized> IntoChainMeta for $p<T> {
+                fn into_chain(&self) -> ChainMeta {
+                    (**self).into_chain()
+                }
+
+                fn into_chain_field(&self) -> Option<ChainMeta> {
+                    (**self).into_chain_field()
+                }
+            }
+
+            impl<T: FromChainMeta> FromChainMeta for $p<T> {
+                fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+                    T::try_from_chain(chain).map($p::new)
+                }
+
+                fn try_from_chain_with(
+                    chain: Option<&ChainMeta>,
+                    options: &LoadOptions,
+                ) -> Result<Self, ChainError> {
+                    T::try_from_chain_with(chain, options).map($p::new)
+                }
+            }
+        )*
+    };
+}
+
+pointer_impl!(Box, Rc, Arc);
+
+/// load every element of `SubChains`,
+/// elements failed to load are dropped if lenient, otherwise fail with its index
+fn load_items<T: FromChainMeta>(
+    chain: Option<&ChainMeta>,
+    options: &LoadOptions,
+) -> Result<Vec<T>, ChainError> {
+    let v = match require(chain)? {
+        ChainMeta::SubChains(v) => v,
+        other => return Err(ChainError::mismatch("SubChains", other)),
+    };
+    let items = v
+        .iter()
+        .enumerate()
+        .map(|(i, f)| T::try_from_chain_with(Some(f), options).map_err(|e| e.at_index(i)));
+    if options.is_lenient() {
+        Ok(items.filter_map(Result::ok).collect())
+    } else {
+        items.collect()
+    }
+}
+
+/// load every value of `Map`,
+/// values failed to load are dropped if lenient, otherwise fail with its key
+fn load_entries<'m, K, T, I>(entries: I, options: &LoadOptions) -> Result<Vec<(K, T)>, ChainError>
+where
+    K: AsRef<str>,
+    T: FromChainMeta,
+    I: Iterator<Item = (K, &'m ChainMeta)>,
+{
+    let entries = entries.map(|(k, v)| match T::try_from_chain_with(Some(v), options) {
+        Ok(v) => Ok((k, v)),
+        Err(e) => Err(e.at_key(k.as_ref())),
+    });
+    if options.is_lenient() {
+        Ok(entries.filter_map(Result::ok).collect())
+    } else {
+        entries.collect()
+    }
+}
+
+macro_rules! seq_impl {
+    ($($seq:ident<T $(: $($bound:ident)+)?>),*) => {
+        $(
+            impl<T: FromChainMeta $($(+ $bound)+)?> FromChainMeta for $seq<T> {
+                fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+                    Self::try_from_chain_with(chain, &LoadOptions::default())
+                }
+
+                fn try_from_chain_with(
+                    chain: Option<&ChainMeta>,
+                    options: &LoadOptions,
+                ) -> Result<Self, ChainError> {
+                    load_items(chain, options).map(|v| v.into_iter().collect())
+                }
+            }
+        )*
+    };
+}
+
+seq_impl!(Vec<T>, VecDeque<T>, HashSet<T: Eq Hash>, BTreeSet<T: Ord>);
+
+macro_rules! seq_into_impl {
+    ($($seq:ident),*) => {
+        $(
+            impl<T: IntoChainMeta> IntoChainMeta for $seq<T> {
+                fn into_chain(&self) -> ChainMeta {
+                    ChainMeta::SubChains(self.iter().map(|t| t.into_chain()).collect())
+                }
+            }
+        )*
+    };
+}
+
+seq_into_impl!(Vec, VecDeque, BTreeSet);
+
+/// elements of `HashSet` are sorted like keys of `HashMap`, so the chain is the same every time
+impl<T: IntoChainMeta + Ord> IntoChainMeta for HashSet<T> {
     fn into_chain(&self) -> ChainMeta {
-        ChainMeta::Map(self.iter().map(|f| (*f.0, f.1.into_chain())).collect())
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort_unstable();
+        ChainMeta::SubChains(items.into_iter().map(|t| t.into_chain()).collect())
     }
 }
-impl<T: FromChainMeta> FromChainMeta for HashMap<&'static str, T> {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Map(map) = chain? {
-            let map = map
-                .iter()
-                .map(|f| (*f.0, T::from_chain(Some(f.1))))
-                .filter(|f| match f.1 {
-                    Some(_) => true,
-                    None => false,
-                })
-                .map(|f| (f.0, f.1.unwrap()))
-                .collect();
-
-            Some(map)
-        } else {
-            None
+
+/// Chain Key:
+///  keys of a map loaded from `ChainMeta`
+pub trait ChainKey: AsRef<str> + Sized {
+    fn from_key(key: Cow<'static, str>) -> Result<Self, ChainError>;
+}
+
+impl ChainKey for String {
+    fn from_key(key: Cow<'static, str>) -> Result<Self, ChainError> {
+        Ok(key.into_owned())
+    }
+}
+
+impl ChainKey for Cow<'static, str> {
+    fn from_key(key: Cow<'static, str>) -> Result<Self, ChainError> {
+        Ok(key)
+    }
+}
+
+/// only keys written as `&'static str` can be loaded back, keys deserialized are owned
+impl ChainKey for &'static str {
+    fn from_key(key: Cow<'static, str>) -> Result<Self, ChainError> {
+        match key {
+            Cow::Borrowed(k) => Ok(k),
+            Cow::Owned(k) => {
+                Err(ChainError::invalid("owned key can not be `&'static str`").at_key(k))
+            }
         }
     }
 }
 
-impl<T: IntoChainMeta> IntoChainMeta for (T,) {
+macro_rules! map_impl {
+    ($($map:ident<K: $($bound:ident)+> => $sort:literal),*) => {
+        $(
+            /// keys can be `&'static str`, `String` or `Cow<'static, str>`
+            impl<K, T> IntoChainMeta for $map<K, T>
+            where
+                K: Clone + Into<Cow<'static, str>>,
+                T: IntoChainMeta,
+            {
+                fn into_chain(&self) -> ChainMeta {
+                    let mut map: ChainMap =
+                        self.iter().map(|f| (f.0.clone().into(), f.1.into_chain())).collect();
+                    if $sort {
+                        map.sort_keys();
+                    }
+                    ChainMeta::Map(map)
+                }
+            }
+
+            /// keys can be `String`, `Cow<'static, str>` or `&'static str`
+            impl<K, T> FromChainMeta for $map<K, T>
+            where
+                K: ChainKey $(+ $bound)+,
+                T: FromChainMeta,
+            {
+                fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+                    Self::try_from_chain_with(chain, &LoadOptions::default())
+                }
+
+                fn try_from_chain_with(
+                    chain: Option<&ChainMeta>,
+                    options: &LoadOptions,
+                ) -> Result<Self, ChainError> {
+                    match require(chain)? {
+                        ChainMeta::Map(map) => {
+                            let entries = map
+                                .iter()
+                                .map(|(k, v)| K::from_key(k.clone()).map(|k| (k, v)))
+                                .collect::<Result<Vec<_>, _>>()?;
+                            load_entries(entries.into_iter(), options).map(|v| v.into_iter().collect())
+                        }
+                        other => Err(ChainError::mismatch("Map", other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+// keys of `HashMap` are sorted, so the chain is the same every time
+map_impl!(
+    HashMap<K: Eq Hash> => true,
+    BTreeMap<K: Ord> => false,
+    IndexMap<K: Eq Hash> => false
+);
+
+impl<T: IntoChainMeta, const N: usize> IntoChainMeta for [T; N] {
     fn into_chain(&self) -> ChainMeta {
-        ChainMeta::SubChains(vec![self.0.into_chain()])
+        self[..].into_chain()
     }
 }
 
-impl<T: FromChainMeta> FromChainMeta for (T,) {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::SubChains(vc) = chain? {
-            if vc.len() == 1 {
-                let v = T::from_chain(vc.get(0))?;
-                Some((v,))
-            } else {
-                None
-            }
-        } else {
-            None
+impl<T: FromChainMeta, const N: usize> FromChainMeta for [T; N] {
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        Self::try_from_chain_with(chain, &LoadOptions::default())
+    }
+
+    fn try_from_chain_with(
+        chain: Option<&ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Self, ChainError> {
+        match require(chain)? {
+            ChainMeta::SubChains(vc) => vc
+                .iter()
+                .enumerate()
+                .map(|(i, f)| T::try_from_chain_with(Some(f), options).map_err(|e| e.at_index(i)))
+                .collect::<Result<Vec<T>, _>>()?
+                .try_into()
+                .map_err(|v: Vec<T>| {
+                    ChainError::invalid(format!("expected {} elements, found {}", N, v.len()))
+                }),
+            other => Err(ChainError::mismatch("SubChains", other)),
         }
     }
 }
 
+macro_rules! tuple_impl {
+    ($($len:literal => ($($n:tt $t:ident),+))*) => {
+        $(
+            impl<$($t: IntoChainMeta),+> IntoChainMeta for ($($t,)+) {
+                fn into_chain(&self) -> ChainMeta {
+                    ChainMeta::SubChains(vec![$(self.$n.into_chain()),+])
+                }
+            }
+
+            impl<$($t: FromChainMeta),+> FromChainMeta for ($($t,)+) {
+                fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+                    Self::try_from_chain_with(chain, &LoadOptions::default())
+                }
+
+                fn try_from_chain_with(
+                    chain: Option<&ChainMeta>,
+                    options: &LoadOptions,
+                ) -> Result<Self, ChainError> {
+                    match require(chain)? {
+                        ChainMeta::SubChains(vc) if vc.len() == $len => Ok(($(
+                            $t::try_from_chain_with(vc.get($n), options).map_err(|e| e.at_index($n))?,
+                        )+)),
+                        ChainMeta::SubChains(vc) => Err(ChainError::invalid(format!(
+                            "expected {} elements, found {}",
+                            $len,
+                            vc.len()
+                        ))),
+                        other => Err(ChainError::mismatch("SubChains", other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+tuple_impl! {
+    1 => (0 T0)
+    2 => (0 T0, 1 T1)
+    3 => (0 T0, 1 T1, 2 T2)
+    4 => (0 T0, 1 T1, 2 T2, 3 T3)
+    5 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4)
+    6 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5)
+    7 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6)
+    8 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7)
+    9 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8)
+    10 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9)
+    11 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10)
+    12 => (0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11)
+}
+
 impl<T: IntoChainMeta> IntoChainMeta for [T] {
     fn into_chain(&self) -> ChainMeta {
         ChainMeta::SubChains(self.iter().map(|f|f.into_chain()).collect())
@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+use crate::{ChainError, ChainKey, ChainMeta, Field, FromChainMeta, IndexMap, IntoChainMeta};
+
+// a blanket `impl<T: IntoChainMeta> From<T> for ChainMeta` conflict with `From<T> for T`,
+// so every type is listed, generics of a type with their bounds are inside `[...]`,
+// types derived `IntoChainMeta`/`FromChainMeta` get the same impls from the derives
+
+macro_rules! from_impl {
+    ($([$($g:tt)*] $ty:ty),* $(,)?) => {
+        $(
+            impl<$($g)*> From<$ty> for ChainMeta {
+                fn from(value: $ty) -> Self {
+                    value.into_chain()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! try_from_impl {
+    ($([$($g:tt)*] $ty:ty),* $(,)?) => {
+        $(
+            impl<$($g)*> TryFrom<ChainMeta> for $ty {
+                type Error = ChainError;
+
+                fn try_from(value: ChainMeta) -> Result<Self, ChainError> {
+                    <$ty as FromChainMeta>::try_from_chain(Some(&value))
+                }
+            }
+        )*
+    };
+}
+
+from_impl!(
+    [] u8, [] u16, [] u32, [] u64, [] i8, [] i16, [] i32, [] i64, [] f32, [] f64,
+    [] bool, [] char, [] String, ['a] &'a str, ['a] Cow<'a, str>,
+    [T: IntoChainMeta] Option<T>,
+    [T: IntoChainMeta] Field<T>,
+    [T: IntoChainMeta] Vec<T>,
+    [T: IntoChainMeta] VecDeque<T>,
+    [T: IntoChainMeta + Ord] HashSet<T>,
+    [T: IntoChainMeta] BTreeSet<T>,
+    [T: IntoChainMeta, const N: usize] [T; N],
+    ['a, T: IntoChainMeta] &'a [T],
+    [T: IntoChainMeta + ?Sized] Box<T>,
+    [T: IntoChainMeta + ?Sized] Rc<T>,
+    [T: IntoChainMeta + ?Sized] Arc<T>,
+    [K: Clone + Into<Cow<'static, str>>, T: IntoChainMeta] HashMap<K, T>,
+    [K: Clone + Into<Cow<'static, str>>, T: IntoChainMeta] BTreeMap<K, T>,
+    [K: Clone + Into<Cow<'static, str>>, T: IntoChainMeta] IndexMap<K, T>,
+    [] Value,
+    [] Map<String, Value>,
+);
+
+// `Option<T>`, the pointers and `Value` already have `From<ChainMeta>` or `From<T>`,
+// which make a `TryFrom<ChainMeta>`
+try_from_impl!(
+    [] u8, [] u16, [] u32, [] u64, [] i8, [] i16, [] i32, [] i64, [] f32, [] f64,
+    [] bool, [] char, [] String, ['a] Cow<'a, str>,
+    [T: FromChainMeta] Field<T>,
+    [T: FromChainMeta] Vec<T>,
+    [T: FromChainMeta] VecDeque<T>,
+    [T: FromChainMeta + Eq + Hash] HashSet<T>,
+    [T: FromChainMeta + Ord] BTreeSet<T>,
+    [T: FromChainMeta, const N: usize] [T; N],
+    [K: ChainKey + Eq + Hash, T: FromChainMeta] HashMap<K, T>,
+    [K: ChainKey + Ord, T: FromChainMeta] BTreeMap<K, T>,
+    [K: ChainKey + Eq + Hash, T: FromChainMeta] IndexMap<K, T>,
+    [] Map<String, Value>,
+);
+
+macro_rules! tuple_convert {
+    ($(($($t:ident),+))*) => {
+        $(
+            from_impl!([$($t: IntoChainMeta),+] ($($t,)+));
+            try_from_impl!([$($t: FromChainMeta),+] ($($t,)+));
+        )*
+    };
+}
+
+tuple_convert! {
+    (T0)
+    (T0, T1)
+    (T0, T1, T2)
+    (T0, T1, T2, T3)
+    (T0, T1, T2, T3, T4)
+    (T0, T1, T2, T3, T4, T5)
+    (T0, T1, T2, T3, T4, T5, T6)
+    (T0, T1, T2, T3, T4, T5, T6, T7)
+    (T0, T1, T2, T3, T4, T5, T6, T7, T8)
+    (T0, T1, T2, T3, T4, T5, T6, T7, T8, T9)
+    (T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10)
+    (T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11)
+}
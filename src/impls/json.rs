@@ -1,38 +1,72 @@
-use crate::{IntoChainMeta};
 use serde_json::{Map, Value};
 
-use crate::ChainMeta;
+use crate::{ChainError, ChainMeta, FromChainMeta, IntoChainMeta, Number};
 
-impl IntoChainMeta for Value {
- 
+use super::require;
 
+impl IntoChainMeta for Value {
     fn into_chain(&self) -> ChainMeta {
         match self {
             Value::Null => ChainMeta::Null,
             Value::Bool(b) => b.into_chain(),
             Value::Number(n) => {
-                if n.is_u64(){
+                if n.is_u64() {
                     n.as_u64().into_chain()
-                }else if n.is_i64(){
+                } else if n.is_i64() {
                     n.as_i64().into_chain()
-                }else{
+                } else {
                     n.as_f64().into_chain()
                 }
-            },
+            }
             Value::String(s) => s.into_chain(),
-            Value::Array(v) => {
-               v.into_chain()
-            },
+            Value::Array(v) => v.into_chain(),
             Value::Object(obj) => obj.into_chain(),
         }
     }
 }
 
-impl IntoChainMeta for Map<String,Value> {
+impl IntoChainMeta for Map<String, Value> {
     fn into_chain(&self) -> ChainMeta {
-        let map=self.into_iter()
-        .map(|f| (f.0.clone(),f.1.into_chain()))
-        .collect();
-        ChainMeta::MapOwn(map)
+        let map = self
+            .iter()
+            .map(|(k, v)| (k.clone().into(), v.into_chain()))
+            .collect();
+        ChainMeta::Map(map)
+    }
+}
+
+/// `NaN` and infinity can not be json number, they become `Null` like `serde_json::json!`
+impl From<ChainMeta> for Value {
+    fn from(chain: ChainMeta) -> Self {
+        match chain {
+            ChainMeta::Null => Value::Null,
+            ChainMeta::Str(s) => Value::String(s),
+            ChainMeta::Bool(b) => Value::Bool(b),
+            ChainMeta::Num(Number::N(n)) => Value::from(n),
+            ChainMeta::Num(Number::T(n)) => Value::from(n),
+            ChainMeta::Num(Number::Float(f)) => Value::from(f),
+            ChainMeta::SubChains(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+            ChainMeta::Map(map) => Value::Object(into_object(map.into_iter())),
+        }
+    }
+}
+
+fn into_object<K: Into<String>>(iter: impl Iterator<Item = (K, ChainMeta)>) -> Map<String, Value> {
+    iter.map(|(k, v)| (k.into(), Value::from(v))).collect()
+}
+
+/// any chain is a json value, only missing chain fails
+impl FromChainMeta for Value {
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        Ok(Value::from(require(chain)?.clone()))
+    }
+}
+
+impl FromChainMeta for Map<String, Value> {
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        match require(chain)? {
+            ChainMeta::Map(map) => Ok(into_object(map.clone().into_iter())),
+            other => Err(ChainError::mismatch("Map", other)),
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,13 +1,22 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::iter::FromIterator;
 
-use crate::{ChainMeta, IntoChainMeta, LoadFormMap, Number};
+use crate::{ChainError, ChainMap, ChainMeta, IntoChainMeta, LoadFormMap, LoadOptions, Number};
 use crate::{FromChainMeta, MessageChain};
-use serde::{ser::SerializeStruct, Serialize};
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
 
+mod access;
 mod collection;
+mod convert;
 mod json;
 mod number;
 
+pub use access::ChainIndex;
+pub use collection::ChainKey;
+
 impl<T> IntoChainMeta for Option<T>
 where
     T: IntoChainMeta,
@@ -21,15 +30,17 @@ where
 }
 
 impl<T: FromChainMeta> FromChainMeta for Option<T> {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let Some(_) = chain {
-            if let Some(ChainMeta::Null) = chain {
-                Some(None)
-            } else {
-                Some(Some(T::from_chain(chain)?))
-            }
-        } else {
-            Some(None)
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        Self::try_from_chain_with(chain, &LoadOptions::default())
+    }
+
+    fn try_from_chain_with(
+        chain: Option<&ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Self, ChainError> {
+        match chain {
+            None | Some(ChainMeta::Null) => Ok(None),
+            Some(_) => Ok(Some(T::try_from_chain_with(chain, options)?)),
         }
     }
 }
@@ -40,15 +51,51 @@ impl IntoChainMeta for ChainMeta {
     }
 }
 impl FromChainMeta for ChainMeta {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        Some(chain?.clone())
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        Ok(require(chain)?.clone())
     }
 }
 
+/// the chain meta must exist
+pub(crate) fn require(chain: Option<&ChainMeta>) -> Result<&ChainMeta, ChainError> {
+    chain.ok_or_else(ChainError::missing)
+}
+
 impl ChainMeta {
     pub fn into_target<T: FromChainMeta>(&self) -> Option<T> {
         T::from_chain(Some(self))
     }
+
+    pub fn try_into_target<T: FromChainMeta>(&self) -> Result<T, ChainError> {
+        T::try_from_chain(Some(self))
+    }
+
+    /// sort keys of all maps inside,
+    /// equal chains are serialized to the same string after sorted, whatever order they received
+    pub fn sort_keys(&mut self) {
+        match self {
+            ChainMeta::SubChains(v) => v.iter_mut().for_each(ChainMeta::sort_keys),
+            ChainMeta::Map(map) => {
+                map.sort_keys();
+                map.values_mut().for_each(ChainMeta::sort_keys);
+            }
+            _ => {}
+        }
+    }
+
+    /// name of the variant, used in error report
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            ChainMeta::Null => "Null",
+            ChainMeta::Str(_) => "Str",
+            ChainMeta::Bool(_) => "Bool",
+            ChainMeta::Num(Number::N(_)) => "Num(N)",
+            ChainMeta::Num(Number::T(_)) => "Num(T)",
+            ChainMeta::Num(Number::Float(_)) => "Num(Float)",
+            ChainMeta::SubChains(_) => "SubChains",
+            ChainMeta::Map(_) => "Map",
+        }
+    }
 }
 
 impl Serialize for ChainMeta {
@@ -67,31 +114,116 @@ impl Serialize for ChainMeta {
             ChainMeta::Null => serializer.serialize_none(),
             ChainMeta::SubChains(sc) => sc.serialize(serializer),
             ChainMeta::Map(map) => map.serialize(serializer),
-            ChainMeta::MapOwn(map) => map.serialize(serializer),
         }
     }
 }
 
+impl<K: Into<Cow<'static, str>>> FromIterator<(K, ChainMeta)> for ChainMeta {
+    fn from_iter<I: IntoIterator<Item = (K, ChainMeta)>>(iter: I) -> Self {
+        ChainMeta::Map(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainMeta {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ChainMetaVisitor)
+    }
+}
+
+struct ChainMetaVisitor;
+
+impl<'de> Visitor<'de> for ChainMetaVisitor {
+    type Value = ChainMeta;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid chain meta")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(ChainMeta::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        // same as json `Value`, none negative number always be `N`
+        if v >= 0 {
+            Ok(ChainMeta::Num(Number::N(v as u64)))
+        } else {
+            Ok(ChainMeta::Num(Number::T(v)))
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(ChainMeta::Num(Number::N(v)))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(ChainMeta::Num(Number::Float(v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(ChainMeta::Str(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(ChainMeta::Str(v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ChainMeta::Null)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ChainMeta::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ChainMeta::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(elem) = seq.next_element()? {
+            v.push(elem);
+        }
+        Ok(ChainMeta::SubChains(v))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut m = ChainMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((k, v)) = map.next_entry::<String, ChainMeta>()? {
+            m.insert(k.into(), v);
+        }
+        Ok(ChainMeta::Map(m))
+    }
+}
+
 impl Serialize for dyn MessageChain {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let all_data = self.get_all();
-        let mut data = serializer.serialize_struct("chainMeta", all_data.len() + 1)?;
-        data.serialize_field("type", &self.get_type())?;
-
-        let _t = all_data
-            .iter()
-            .map(|f| data.serialize_field(f.0, &f.1))
-            .collect::<Vec<_>>();
-
-        data.end()
+        crate::__private::serialize_message(self, serializer)
     }
 }
 
 impl dyn MessageChain {
     pub fn into_target<T: LoadFormMap>(&self) -> Option<T> {
+        self.try_into_target().ok()
+    }
+
+    pub fn try_into_target<T: LoadFormMap>(&self) -> Result<T, ChainError> {
         if T::type_eq(self.get_type()){
 
             let mut map = HashMap::new();
@@ -100,9 +232,9 @@ impl dyn MessageChain {
             for (k, v) in self.get_all() {
                 map.insert(k.to_string(), v);
             }
-            T::load_from_map(&map)
+            T::try_load_from_map(&map)
         }else {
-            None
+            Err(ChainError::unknown_type(self.get_type()))
         }
     }
 }
@@ -110,11 +242,13 @@ impl dyn MessageChain {
 
 #[cfg(test)]
 mod test{
-    use serde_json::to_string;
+    use serde_json::{from_str, to_string, Map, Value};
 
     use super::*;
+    use crate::{ErrorKind, IndexMap};
+    use std::collections::BTreeMap;
 
-    #[derive(MessageChain)]
+    #[derive(MessageChain, LoadFormMap)]
 
     struct Text {
         txt:String,
@@ -151,4 +285,379 @@ mod test{
         println!("{}",res.unwrap())
         
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_json2chain_meta() {
+        let json = r#"{
+            "type": "Image",
+            "imageId": "{01E9451B-70ED-EAE3-B37C-101F1EEBF5B5}.mirai",
+            "url": null,
+            "size": 1024,
+            "offset": -1,
+            "scale": 0.5,
+            "flash": false,
+            "tags": ["a", 1]
+        }"#;
+
+        let meta: ChainMeta = from_str(json).unwrap();
+        let value: Value = from_str(json).unwrap();
+
+        assert_eq!(meta, value.into_chain());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        use serde_json::json;
+        use std::convert::TryFrom;
+
+        let values = vec![
+            json!(null),
+            json!(true),
+            json!(0),
+            json!(u64::MAX),
+            json!(i64::MIN),
+            json!(-1),
+            json!(0.5),
+            json!(-1e300),
+            json!(1.0),
+            json!("好耶"),
+            json!([]),
+            json!({}),
+            json!([1, "a", null, [false], {"k": -2.5}]),
+            json!({"type": "Image", "size": {"w": 18446744073709551615u64, "h": -3}, "tags": ["a"]}),
+        ];
+        for value in values {
+            let meta = value.into_chain();
+            assert_eq!(Value::from(meta.clone()), value);
+            assert_eq!(Value::try_from_chain(Some(&meta)), Ok(value.clone()));
+            assert_eq!(to_string(&meta).unwrap(), to_string(&value).unwrap());
+        }
+
+        assert_eq!(Value::from(f64::NAN.into_chain()), Value::Null);
+        assert_eq!(Value::try_from_chain(None), Err(ChainError::missing()));
+
+        let object = json!({"a": 1, "b": [true]});
+        let map = Map::try_from(object.into_chain()).unwrap();
+        assert_eq!(Value::Object(map), object);
+        let err = Map::<String, Value>::try_from(ChainMeta::from(1)).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::TypeMismatch { expected: "Map", .. }));
+    }
+
+    #[derive(MessageChain, LoadFormMap)]
+    struct App {
+        content: Value,
+        meta: Map<String, Value>,
+    }
+
+    #[test]
+    fn test_json_field() {
+        let json = r#"{"type":"App","content":[1,{"a":null}],"meta":{"id":-1}}"#;
+        let map: HashMap<String, ChainMeta> = from_str(json).unwrap();
+        let app = App::try_load_from_map(&map).unwrap();
+        assert_eq!(app.content, serde_json::json!([1, {"a": null}]));
+        assert_eq!(app.meta["id"], -1);
+
+        let bo: Box<dyn MessageChain> = Box::new(app);
+        assert_eq!(to_string(&bo).unwrap(), json);
+    }
+
+    #[test]
+    fn test_stable_order() {
+        let json = r#"{"type":"Image","url":null,"imageId":"a","size":{"w":1,"h":2}}"#;
+        let meta: ChainMeta = from_str(json).unwrap();
+        assert_eq!(to_string(&meta).unwrap(), json);
+
+        let mut sorted = meta.clone();
+        sorted.sort_keys();
+        assert_eq!(
+            to_string(&sorted).unwrap(),
+            r#"{"imageId":"a","size":{"h":2,"w":1},"type":"Image","url":null}"#
+        );
+
+        let map: HashMap<String, u8> = (0..32).map(|i| (format!("k{:02}", i), i)).collect();
+        let keys = match map.into_chain() {
+            ChainMeta::Map(map) => map.keys().map(|k| k.to_string()).collect::<Vec<_>>(),
+            _ => panic!("HashMap should be Map"),
+        };
+        let mut expect = keys.clone();
+        expect.sort();
+        assert_eq!(keys, expect);
+
+        // each `HashSet` has its own hash seed, the elements are sorted as well
+        let expect: Vec<String> = (0..32).map(|i| format!("k{:02}", i)).collect();
+        let a: std::collections::HashSet<String> = expect.iter().cloned().collect();
+        let b: std::collections::HashSet<String> = expect.iter().rev().cloned().collect();
+        assert_eq!(
+            to_string(&a.into_chain()).unwrap(),
+            to_string(&b.into_chain()).unwrap()
+        );
+        assert_eq!(a.into_chain(), expect.into_chain());
+    }
+
+    #[test]
+    fn test_accessors() {
+        let meta: ChainMeta =
+            from_str(r#"{"id":-1,"qq":18446744073709551615,"f":1.5,"ok":true,"list":["a",null]}"#)
+                .unwrap();
+        assert_eq!(meta["id"].as_i64(), Some(-1));
+        assert_eq!(meta["id"].as_u64(), None);
+        assert_eq!(meta["qq"].as_u64(), Some(u64::MAX));
+        assert_eq!(meta["qq"].as_i64(), None);
+        assert_eq!(meta["f"].as_f64(), Some(1.5));
+        assert_eq!(meta["f"].as_i64(), None);
+        assert_eq!(meta["ok"].as_bool(), Some(true));
+        assert_eq!(meta["list"][0].as_str(), Some("a"));
+        assert!(meta["list"][1].is_null());
+        assert_eq!(meta["list"].as_array().map(Vec::len), Some(2));
+        assert_eq!(meta.as_object().map(|m| m.len()), Some(5));
+
+        assert!(meta.get("nope").is_none());
+        assert!(meta.get(0).is_none());
+        assert!(meta["nope"]["deeper"][3].is_null());
+        let key = String::from("ok");
+        assert_eq!(meta.get(&key), meta.get(key.as_str()));
+        assert_eq!(meta[key], ChainMeta::Bool(true));
+    }
+
+    #[test]
+    fn test_from_try_from() {
+        use std::convert::TryFrom;
+
+        assert_eq!(ChainMeta::from(12u8), ChainMeta::Num(Number::N(12)));
+        assert_eq!(ChainMeta::from("a"), ChainMeta::Str(String::from("a")));
+        assert_eq!(ChainMeta::from(None::<i32>), ChainMeta::Null);
+        let meta = ChainMeta::from(vec![(1u8, 'a'), (2, 'b')]);
+        assert_eq!(<Vec<(u8, char)>>::try_from(meta).unwrap()[1].1, 'b');
+
+        let map: BTreeMap<String, Vec<i64>> =
+            vec![(String::from("k"), vec![-1, 2])].into_iter().collect();
+        let meta: ChainMeta = map.clone().into();
+        assert_eq!(meta["k"][0].as_i64(), Some(-1));
+        assert_eq!(BTreeMap::try_from(meta).unwrap(), map);
+
+        let err = u8::try_from(ChainMeta::from(-1i32)).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Overflow { target: "u8", .. }));
+        assert!(String::try_from(ChainMeta::Null).is_err());
+    }
+
+    #[test]
+    fn test_json2chain_meta_load() {
+        let meta: ChainMeta = from_str(r#"{"type":"Text","txt":"111","showAll":true}"#).unwrap();
+
+        if let ChainMeta::Map(map) = meta {
+            let map = map.into_iter().map(|(k, v)| (k.into_owned(), v)).collect();
+            let text = Text::load_from_map(&map).unwrap();
+            assert_eq!(text.txt, "111");
+            assert_eq!(text.show_all, Some(true));
+        } else {
+            panic!("json object should be Map")
+        }
+    }
+
+    #[test]
+    fn test_number_coercion() {
+        let n = ChainMeta::Num(Number::N(1620000000));
+        assert_eq!(n.try_into_target::<i64>(), Ok(1620000000));
+        assert_eq!(n.try_into_target::<u32>(), Ok(1620000000));
+        assert_eq!(n.try_into_target::<f64>(), Ok(1620000000.0));
+        assert_eq!(
+            n.try_into_target::<i16>().unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "i16",
+                value: Number::N(1620000000)
+            }
+        );
+
+        let t = ChainMeta::Num(Number::T(-3));
+        assert_eq!(t.try_into_target::<i8>(), Ok(-3));
+        assert_eq!(t.try_into_target::<f32>(), Ok(-3.0));
+        assert_eq!(
+            t.try_into_target::<u64>().unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "u64",
+                value: Number::T(-3)
+            }
+        );
+
+        let f = ChainMeta::Num(Number::Float(0.5));
+        assert_eq!(f.try_into_target::<f64>(), Ok(0.5));
+        assert_eq!(
+            f.try_into_target::<u8>().unwrap_err().kind(),
+            &ErrorKind::TypeMismatch {
+                expected: "Num",
+                actual: "Num(Float)"
+            }
+        );
+    }
+
+    #[test]
+    fn test_numeric_strings() {
+        let s = ChainMeta::Str("1141451919".to_string());
+        assert!(s.try_into_target::<u64>().is_err());
+
+        let options = LoadOptions::new().numeric_strings(true);
+        assert_eq!(u64::try_from_chain_with(Some(&s), &options), Ok(1141451919));
+        assert_eq!(f64::try_from_chain_with(Some(&s), &options), Ok(1141451919.0));
+        assert_eq!(
+            Option::<Vec<i64>>::try_from_chain_with(
+                Some(&ChainMeta::SubChains(vec![s.clone()])),
+                &options
+            ),
+            Ok(Some(vec![1141451919]))
+        );
+        assert_eq!(
+            u8::try_from_chain_with(Some(&s), &options).unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "u8",
+                value: Number::N(1141451919)
+            }
+        );
+        assert!(u8::try_from_chain_with(Some(&"1a".into_chain()), &options).is_err());
+
+        // out of range of `Number` is saturated in the report
+        let huge = "99999999999999999999999".into_chain();
+        assert_eq!(
+            u64::try_from_chain_with(Some(&huge), &options).unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "u64",
+                value: Number::N(u64::MAX)
+            }
+        );
+        let tiny = "-99999999999999999999999".into_chain();
+        assert_eq!(
+            i8::try_from_chain_with(Some(&tiny), &options).unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "i8",
+                value: Number::T(i64::MIN)
+            }
+        );
+    }
+
+    #[test]
+    fn test_float_precision() {
+        let exact = ChainMeta::Num(Number::N(1 << 53));
+        assert_eq!(exact.try_into_target::<f64>(), Ok(9007199254740992.0));
+
+        let n = ChainMeta::Num(Number::N((1 << 53) + 1));
+        assert_eq!(
+            n.try_into_target::<f64>().unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "f64",
+                value: Number::N((1 << 53) + 1)
+            }
+        );
+        let t = ChainMeta::Num(Number::T(-(1 << 24) - 1));
+        assert_eq!(
+            t.try_into_target::<f32>().unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "f32",
+                value: Number::T(-(1 << 24) - 1)
+            }
+        );
+        let t = ChainMeta::Num(Number::T(-(1 << 24)));
+        assert_eq!(t.try_into_target::<f32>(), Ok(-16777216.0));
+
+        // numeric strings of integers are checked the same way
+        let options = LoadOptions::new().numeric_strings(true);
+        let s = "16777217".into_chain();
+        assert_eq!(
+            f32::try_from_chain_with(Some(&s), &options).unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "f32",
+                value: Number::N(16777217)
+            }
+        );
+        assert_eq!(f64::try_from_chain_with(Some(&s), &options), Ok(16777217.0));
+        let s = "9007199254740993".into_chain();
+        assert_eq!(
+            f64::try_from_chain_with(Some(&s), &options).unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "f64",
+                value: Number::N(9007199254740993)
+            }
+        );
+        let huge = "-999999999999999999999999999999999999999".into_chain();
+        assert_eq!(
+            f64::try_from_chain_with(Some(&huge), &options).unwrap_err().kind(),
+            &ErrorKind::Overflow {
+                target: "f64",
+                value: Number::T(i64::MIN)
+            }
+        );
+        let s = " 1.5 ".into_chain();
+        assert_eq!(f32::try_from_chain_with(Some(&s), &options), Ok(1.5));
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Profile {
+        tags: std::collections::BTreeSet<String>,
+        extra: HashMap<String, u32>,
+        pos: [i32; 2],
+        sign: char,
+    }
+
+    #[test]
+    fn test_collections_load() {
+        let meta: ChainMeta = from_str(
+            r#"{"type":"Profile","tags":["b","a","b"],"extra":{"level":3},"pos":[1,-2],"sign":"+"}"#,
+        )
+        .unwrap();
+        let map = match meta {
+            ChainMeta::Map(map) => map.into_iter().map(|(k, v)| (k.into_owned(), v)).collect(),
+            _ => panic!("json object should be Map"),
+        };
+        let profile = Profile::try_load_from_map(&map).unwrap();
+
+        assert_eq!(profile.tags.iter().collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(profile.extra.get("level"), Some(&3));
+        assert_eq!(profile.pos, [1, -2]);
+        assert_eq!(profile.sign, '+');
+
+        let mut back = HashMap::new();
+        for (k, v) in profile.get_all() {
+            back.insert(k.to_string(), v);
+        }
+        back.insert("type".to_string(), "Profile".into_chain());
+        assert_eq!(Profile::try_load_from_map(&back), Ok(profile));
+    }
+
+    #[test]
+    fn test_collections_from_chain() {
+        let chain = (1_u8, "a".to_string(), -1_i64).into_chain();
+        assert_eq!(
+            chain.try_into_target::<(u8, String, i64)>(),
+            Ok((1, "a".to_string(), -1))
+        );
+        assert_eq!(
+            chain.try_into_target::<(u8, u8, u8)>().unwrap_err().path(),
+            &[crate::PathSeg::Index(1)]
+        );
+        assert!(chain.try_into_target::<[u8; 2]>().is_err());
+
+        let owned = BTreeMap::from([("k".to_string(), 1_u8)]).into_chain();
+        let borrowed = HashMap::from([("k", 1_u8)]).into_chain();
+        assert_eq!(owned.try_into_target(), Ok(HashMap::from([("k".to_string(), 1_u8)])));
+        assert_eq!(borrowed.try_into_target(), Ok(BTreeMap::from([("k".to_string(), 1_u8)])));
+        // where the keys come from makes no difference
+        assert_eq!(owned, borrowed);
+        assert_eq!(
+            borrowed.try_into_target(),
+            Ok(IndexMap::from([(Cow::Borrowed("k"), 1_u8)]))
+        );
+
+        // `&'static str` keys load back from keys written as `&'static str` only
+        assert_eq!(borrowed.try_into_target(), Ok(HashMap::from([("k", 1_u8)])));
+        let err = from_str::<ChainMeta>(r#"{"k":1}"#)
+            .unwrap()
+            .try_into_target::<HashMap<&'static str, u8>>()
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Invalid(_)));
+        assert_eq!(err.path(), &[crate::PathSeg::Key("k".to_string())]);
+
+        let s = "好耶".into_chain();
+        assert_eq!(s.try_into_target(), Ok(std::borrow::Cow::<str>::Borrowed("好耶")));
+        assert_eq!(s.try_into_target(), Ok(std::rc::Rc::new("好耶".to_string())));
+        assert!(s.try_into_target::<char>().is_err());
+        assert_eq!(Box::new(1_u8).into_chain(), ChainMeta::Num(Number::N(1)));
+    }
+}
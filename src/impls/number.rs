@@ -1,7 +1,9 @@
-use std::u16;
-
 use crate::{ChainMeta, IntoChainMeta};
-use crate::{FromChainMeta, Number};
+use crate::{ChainError, FromChainMeta, LoadOptions, Number};
+use std::convert::TryFrom;
+use std::num::IntErrorKind;
+
+use super::require;
 
 impl IntoChainMeta for u8 {
     fn into_chain(&self) -> ChainMeta {
@@ -23,7 +25,7 @@ impl IntoChainMeta for u32 {
 
 impl IntoChainMeta for u64 {
     fn into_chain(&self) -> ChainMeta {
-        ChainMeta::Num(Number::N(*self as u64))
+        ChainMeta::Num(Number::N(*self))
     }
 }
 
@@ -44,7 +46,7 @@ impl IntoChainMeta for i32 {
 }
 impl IntoChainMeta for i64 {
     fn into_chain(&self) -> ChainMeta {
-        ChainMeta::Num(Number::T(*self as i64))
+        ChainMeta::Num(Number::T(*self))
     }
 }
 
@@ -67,158 +69,133 @@ impl IntoChainMeta for bool {
 }
 
 impl FromChainMeta for bool {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Bool(b)=chain?{
-            Some(*b)
-        }else {
-            None
-        }
-    }
-}
-
-impl FromChainMeta for u8 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::N(n) = n {
-                let s = n.to_string();
-                Self::from_str_radix(&s, 10).ok()
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    }
-}
-
-impl FromChainMeta for u16 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::N(n) = n {
-                let s = n.to_string();
-                Self::from_str_radix(&s, 10).ok()
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    }
-}
-
-impl FromChainMeta for u32 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::N(n) = n {
-                let s = n.to_string();
-                Self::from_str_radix(&s, 10).ok()
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    }
-}
-
-impl FromChainMeta for u64 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::N(n) = n {
-                Some(*n)
-            } else {
-                None
-            }
-        } else {
-            None
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        match require(chain)? {
+            ChainMeta::Bool(b) => Ok(*b),
+            other => Err(ChainError::mismatch("Bool", other)),
         }
     }
 }
 
-impl FromChainMeta for i8 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::T(n) = n {
-                let s = n.to_string();
-                Self::from_str_radix(&s, 10).ok()
-            } else {
-                None
+/// load any integer variant, or numeric string if enabled
+fn load_integer(chain: &ChainMeta, options: &LoadOptions) -> Result<i128, ChainError> {
+    match chain {
+        ChainMeta::Num(Number::N(n)) => Ok(*n as i128),
+        ChainMeta::Num(Number::T(n)) => Ok(*n as i128),
+        ChainMeta::Str(s) if options.is_numeric_strings() => s
+            .trim()
+            .parse()
+            .map_err(|_| ChainError::invalid(format!("{:?} is not an integer", s))),
+        other => Err(ChainError::mismatch("Num", other)),
+    }
+}
+
+/// load any number variant, or numeric string if enabled,
+/// integers (numeric strings too) that `target` can not hold exactly are overflow,
+/// `round` gives the integer as `target`
+fn load_float(
+    chain: &ChainMeta,
+    options: &LoadOptions,
+    target: &'static str,
+    round: fn(i128) -> f64,
+) -> Result<f64, ChainError> {
+    let (n, number) = match chain {
+        ChainMeta::Num(Number::N(n)) => (*n as i128, Number::N(*n)),
+        ChainMeta::Num(Number::T(n)) => (*n as i128, Number::T(*n)),
+        ChainMeta::Num(Number::Float(n)) => return Ok(*n),
+        ChainMeta::Str(s) if options.is_numeric_strings() => match parse_integer(s, target)? {
+            Some(n) => (n, overflow_number(n)),
+            None => {
+                return s
+                    .trim()
+                    .parse()
+                    .map_err(|_| ChainError::invalid(format!("{:?} is not a number", s)))
             }
-        } else {
-            None
-        }
-    }
-}
-
-impl FromChainMeta for i16 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::T(n) = n {
-                let s = n.to_string();
-                Self::from_str_radix(&s, 10).ok()
-            } else {
-                None
+        },
+        other => return Err(ChainError::mismatch("Num", other)),
+    };
+    let f = round(n);
+    if f as i128 == n {
+        Ok(f)
+    } else {
+        Err(ChainError::overflow(target, number))
+    }
+}
+
+/// `s` as integer, `None` if not an integer, overflow (saturated) if out of range of `i128`
+fn parse_integer(s: &str, target: &'static str) -> Result<Option<i128>, ChainError> {
+    match s.trim().parse::<i128>() {
+        Ok(n) => Ok(Some(n)),
+        Err(e) => match e.kind() {
+            IntErrorKind::PosOverflow => Err(ChainError::overflow(target, Number::N(u64::MAX))),
+            IntErrorKind::NegOverflow => Err(ChainError::overflow(target, Number::T(i64::MIN))),
+            _ => Ok(None),
+        },
+    }
+}
+
+/// the number in overflow report, saturated if out of range of `Number`
+fn overflow_number(n: i128) -> Number {
+    match (u64::try_from(n), i64::try_from(n)) {
+        (Ok(n), _) => Number::N(n),
+        (_, Ok(n)) => Number::T(n),
+        _ if n > 0 => Number::N(u64::MAX),
+        _ => Number::T(i64::MIN),
+    }
+}
+
+macro_rules! from_chain_integer {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl FromChainMeta for $t {
+                fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+                    Self::try_from_chain_with(chain, &LoadOptions::default())
+                }
+
+                fn try_from_chain_with(
+                    chain: Option<&ChainMeta>,
+                    options: &LoadOptions,
+                ) -> Result<Self, ChainError> {
+                    let n = load_integer(require(chain)?, options)?;
+                    Self::try_from(n)
+                        .map_err(|_| ChainError::overflow(stringify!($t), overflow_number(n)))
+                }
             }
-        } else {
-            None
-        }
-    }
+        )*
+    };
 }
 
-impl FromChainMeta for i32 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::T(n) = n {
-                let s = n.to_string();
-                Self::from_str_radix(&s, 10).ok()
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    }
-}
+from_chain_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
 
-impl FromChainMeta for i64 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::T(n) = n {
-                Some(*n)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    }
-}
 impl FromChainMeta for f32 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::Float(n) = n {
-                let s = n.to_string();
-                let n:f32=s.parse().ok()?;
-                Some(n)
-            } else {
-                None
-            }
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        Self::try_from_chain_with(chain, &LoadOptions::default())
+    }
+
+    fn try_from_chain_with(
+        chain: Option<&ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Self, ChainError> {
+        // integers beyond 2^24 lose precision in `f32`
+        let n = load_float(require(chain)?, options, "f32", |n| n as f32 as f64)?;
+        let f = n as f32;
+        if f.is_infinite() && n.is_finite() {
+            Err(ChainError::overflow("f32", Number::Float(n)))
         } else {
-            None
+            Ok(f)
         }
     }
 }
 
 impl FromChainMeta for f64 {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
-        if let ChainMeta::Num(n) = chain? {
-            if let Number::Float(n) = n {
-                Some(*n)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError> {
+        Self::try_from_chain_with(chain, &LoadOptions::default())
+    }
+
+    fn try_from_chain_with(
+        chain: Option<&ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Self, ChainError> {
+        load_float(require(chain)?, options, "f64", |n| n as f64)
     }
 }
@@ -20,9 +20,10 @@
 //! 对应的结构体为
 //! 
 //! ```rust
+//! # use msg_chain::*;
 //! #[derive(MessageChain, LoadFormMap)]
 //! struct Image{
-//!     imageId:Option<String>,
+//!     image_id:Option<String>,
 //!     url:Option<String>,
 //!     path:Option<String>,
 //!     base64:Option<String>,
@@ -32,18 +33,31 @@
 //! 然后，将`Image`和其他实现了`MessageChain`和`LoadFormMap`一起通过`msg_loader_generate!` 注册,构造函数`message_chain_loader`
 //! 
 //! ```rust
+//! # use msg_chain::*;
+//! # use std::collections::HashMap;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Image{ image_id:Option<String>, url:Option<String> }
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Plain{ text:String }
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct AtAll;
 //! ///构造函数 message_chain_loader 用于生成MessageChain
-//! msg_loader_generate!(Image,Plain,At,AtAll);
+//! msg_loader_generate!(Image,Plain,AtAll);
 //! ```
 //! 
 //! 在获取数据后，通过`message_chain_loader`获取当前`MessageChain`对象*无匹配对象返回 `None`*, 可以通过 `into_target`转换为特定对象*转换不可行会返回 `None`*
 //! 
 //! ```rust
+//! # use msg_chain::*;
+//! # use std::collections::HashMap;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Image{ image_id:Option<String>, url:Option<String> }
+//! # msg_loader_generate!(Image);
 //! let map : HashMap<String, ChainMeta> = map_generate!(
 //!             Image=>
 //!             [
-//!                 imageId: "{01E9451B-70ED-EAE3-B37C-101F1EEBF5B5}.mirai"
-//!                 url: None,
+//!                 imageId: "{01E9451B-70ED-EAE3-B37C-101F1EEBF5B5}.mirai",
+//!                 url: Option::<String>::None
 //!             ]
 //!         );
 //! let res : Box<dyn MessageChain> = message_chain_loader(&map).unwrap();
@@ -52,11 +66,103 @@
 //! 
 //! ```
 //! 
+//! 需要知道失败原因时，使用对应的 `try_load_from_map`，`try_message_chain_loader`，`try_into_target`，
+//! 失败时返回 `ChainError`, 包含出错的字段(camelCase 的键名)与原因
+//! 
+//! ```rust
+//! # use msg_chain::*;
+//! # use std::collections::HashMap;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Image{ image_id:Option<String>, url:Option<String> }
+//! let map : HashMap<String, ChainMeta> = map_generate!(Image=>[imageId: 12_u64]);
+//! let err = Image::try_load_from_map(&map).err().unwrap();
+//! 
+//! assert_eq!(err.to_string(), "`imageId`: expected Str, found Num(N)");
+//! ```
+//! 
+//! 收到的 `"messageChain": [ ... ]` 可以通过 `msg_loader_generate!` 同时生成的 `deserialize_message_chain` 一次转换为 `Vec<Box<dyn MessageChain>>`,
+//! 未注册的 `type` 按 `UnknownPolicy` 处理：`Skip` 丢弃，`Error` 报错，`Raw` 保留为 `RawChain`,
+//! 也可以使用 `msg_loader_generate!(Image, Plain; fallback = RawChain)`，未注册的 `type` 都会加载为 `RawChain`，
+//! `RawChain` 序列化时原样输出收到的全部数据，可以直接转发
+//! 
+//! ```rust
+//! # use msg_chain::*;
+//! # use std::collections::HashMap;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Plain{ text:String }
+//! # msg_loader_generate!(Plain);
+//! let mut de = serde_json::Deserializer::from_str(r#"[{"type":"Plain","text":"好耶"},{"type":"Dice","value":1}]"#);
+//! let chains : Vec<Box<dyn MessageChain>> = deserialize_message_chain(&mut de, UnknownPolicy::Raw).unwrap();
+//! 
+//! assert_eq!(chains[1].get_type(), "Dice");
+//! ```
+//!
+//! 同一个模块中需要多个加载函数时，可以指定函数名 `msg_loader_generate!(pub fn incoming_loader => Plain, Image)`，
+//! 生成的函数返回 `Result<Box<dyn MessageChain>, ChainError>`，需要 `Option` 时使用 `.ok()`，
+//! 这种形式只生成这一个函数，需要 `LoadOptions` 时使用不指定函数名的形式(`try_message_chain_loader_with`)或者 `ChainRegistry`，
+//! 宏内部使用的类型都通过完整路径引用，不需要额外 `use`，同一个类型出现两次时编译报错
+//!
+//! ```rust
+//! # use msg_chain::*;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Plain{ text:String }
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct AtAll;
+//! msg_loader_generate!(pub fn incoming_loader => Plain, AtAll);
+//! msg_loader_generate!(fn outgoing_loader => Plain);
+//! ```
+//!
+//! ```rust,compile_fail,E0119
+//! # use msg_chain::*;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Plain{ text:String }
+//! // error[E0119]: conflicting implementations of trait `MessageTypeListedTwice`
+//! msg_loader_generate!(fn loader => Plain, Plain);
+//! ```
+//!
+//! 需要在运行时组合消息类型时(例如不同 crate 的插件各自提供类型)，可以使用 `ChainRegistry`，
+//! 按 `type` 直接查找加载的类型，同一个 `type` 注册了不同类型时返回 `DuplicateType`，
+//! `merge` 合并两个注册表(有冲突时报错)，`layer` 叠加另一个注册表(冲突时以后者为准)
+//!
+//! ```rust
+//! # use msg_chain::*;
+//! # use std::collections::HashMap;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Plain{ text:String }
+//! let mut registry = ChainRegistry::new();
+//! registry.register::<Plain>().unwrap().register::<RawChain>().unwrap();
+//!
+//! let chain = registry.load(&map_generate!(Dice=>[value: 1_u8])).unwrap();
+//! assert_eq!(chain.get_type(), "Dice");
+//! ```
+//! 
+//! 整数与浮点数之间会自动转换(`u64` 字段可以读取 `Num(T)`，`f64` 字段可以读取整数)，超出目标类型范围或无法精确表示(例如超过 2^53 的整数读取为 `f64`)时返回 `ErrorKind::Overflow`,
+//! 部分数字以字符串形式发送时，可以通过 `LoadOptions::numeric_strings` 或者字段属性 `#[meta(numeric_strings)]` 允许从字符串读取数字，字符串中的整数同样检查范围与精度
+//!
+//! ```rust
+//! # use msg_chain::*;
+//! # use std::collections::HashMap;
+//! # #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+//! # struct Face{ face_id:u32 }
+//! let map = map_generate!(Face=>[faceId: "12"]);
+//! assert!(Face::load_from_map(&map).is_none());
+//!
+//! let options = LoadOptions::new().numeric_strings(true);
+//! assert_eq!(Face::try_load_from_map_with(&map, &options), Ok(Face{ face_id: 12 }));
+//! ```
+//!
+//! `Vec`，`HashMap` 等集合中有元素加载失败时，默认整个字段加载失败，`ChainError` 中会包含出错元素的下标或键(`nodeList[1]`),
+//! 需要丢弃加载失败的元素时，使用 `LoadOptions::new().lenient(true)`
+//!
 //! 然后就可以快乐使用了
 //! 
 //! * 为了方便快捷得构造 `HashMap` 提供了相关宏`map_generate!`帮助构造,以下为使用方法
 //! 
 //! ```rust
+//! # use msg_chain::*;
+//! # use std::collections::HashMap;
+//! # #[derive(MessageChain, LoadFormMap)]
+//! # struct Image{ image_id:Option<String>, url:Option<String>, path:Option<String>, base64:Option<String> }
 //!     //以下将会构造出
 //!     //{
 //!     //  "type" : ChainMeta::Str("AtAll")
@@ -82,7 +188,7 @@
 //!         );
 //!     
 //!     let t = Image{
-//!             imageId:Some("{01E9451B-70ED-EAE3-B37C-101F1EEBF5B5}.mirai".to_string()),
+//!             image_id:Some("{01E9451B-70ED-EAE3-B37C-101F1EEBF5B5}.mirai".to_string()),
 //!             url:None,
 //!             path:None,
 //!             base64:None
@@ -98,15 +204,199 @@
 //!     let map3: HashMap<String, ChainMeta> = map_generate!( &t );
 //! ```
 //! 
+//! * 开启 feature `mirai` 后，`msg_chain::mirai` 提供 mirai-api-http 全部消息类型
+//!   以及可以加载全部类型的 `msg_chain::mirai::message_chain_loader`
+//! 
+//! * 字段校验 `#[chain_meta_limit]`，放在 `#[derive(...)]` 之前，通过字段上的 `#[limit(...)]` 设置限制
+//!   * `len(min = 1, max = 4500)` 长度范围，`non_empty` 不能为空
+//!   * `range(1..=10000)` 数值范围
+//!   * `one_of("a", "b")` 只能为其中之一
+//!   * `regex = "..."` 需要匹配正则表达式，需要开启 feature `regex`，正则表达式在编译时检查，只会构建一次
+//!   * `Option` 字段为 `None` 时不检查
+//!   * 会实现 `ChainValidate`，发送前可以调用 `validate()` 检查，`LoadFormMap` 加载时不满足限制的返回 `ErrorKind::Invalid`
+//! 
+//! ```rust
+//! # use msg_chain::*;
+//! #[chain_meta_limit]
+//! #[derive(MessageChain, LoadFormMap)]
+//! struct Plain {
+//!     #[limit(len(max = 4500), non_empty)]
+//!     text: String,
+//! }
+//! 
+//! let plain = Plain { text: String::new() };
+//! assert!(plain.validate().is_err());
+//! ```
+//! 
 //! * `MessageChain`自动实现
 //!   * 类型为 `namedStruct` 或者 `Unit`
+//!   * 或者为 tuple struct，每个字段都需要通过 `#[meta(rename = "key")]` 指定键名(`struct Dice(#[meta(rename = "value")] u8)`)
+//!   * 支持泛型、生命周期与 const 泛型(`Wrapper<T, const N: usize>`)，使用了泛型参数的字段会自动加上 `IntoChainMeta`/`FromChainMeta` 约束
+//!   * 或者为 `enum`，每个变体可以包装一个已实现 `MessageChain`/`LoadFormMap` 的类型(`Plain(Plain)`)，
+//!     也可以直接作为消息(`Dice { value: u8 }`，`AtAll`)，加载时根据 `type` 选择变体，之后即可 `match`
 //!   * 内部变量全部都实现了`IntoChainMeta` 和 `FromChainMeta`
-//!   * 为了方便处理，所有实现了`MessageChain`都会实现`Serialize`
-pub use from_chain_derive::LoadFormMap;
-pub use msg_chain_derive::MessageChain;
+//!     * 已实现：数字，`bool`，`char`，`String`，`Cow<str>`，`Option`，`Vec`，`VecDeque`，`HashSet`，`BTreeSet`，`[T; N]`，
+//!       元组(最多 12 个元素)，`Box`/`Rc`/`Arc`，`HashMap`，`BTreeMap`，`IndexMap`(键为 `String`，`Cow<'static, str>` 或 `&'static str`，`&'static str` 只能读取同样以 `&'static str` 写入的键)
+//!     * 结构不固定的字段(例如 `App` 消息的内容)可以使用 `serde_json::Value` 或 `serde_json::Map<String, Value>`，
+//!       `Value` 与 `ChainMeta` 可以互相转换(`value.into_chain()`，`Value::from(meta)`)，转换回来与原值相同
+//!     * `Option<T>` 不区分键不存在与 `null`，需要区分时(例如修改群员信息只发送修改的键)使用 `Field<T>`：
+//!       `Missing` 键不存在，`Null` 为 `null`，`Value(T)`，`get_all` 与 `map_generate!(&value)` 会省略 `Missing` 的键
+//!     * 没有 `type` 的嵌套对象(发送者信息，转发消息的节点)和取值固定的字符串，`#[derive(IntoChainMeta, FromChainMeta)]` 后可以直接作为消息的字段
+//!       * newtype(`struct GroupId(u64)`)读写时与内部的值相同
+//!       * struct 读写为不含 `type` 的 `Map`，字段属性与消息相同
+//!       * 只有 unit 变体的 enum 读写为字符串，默认为变体名，变体上可以使用 `rename`/`alias`，enum 上可以使用 `rename_all`
+//!
+//!   ```rust
+//!   # use msg_chain::*;
+//!   #[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq)]
+//!   #[meta(rename_all = "SCREAMING_SNAKE_CASE")]
+//!   enum Permission {
+//!       Owner,
+//!       Administrator,
+//!       Member,
+//!   }
+//!
+//!   #[derive(IntoChainMeta, FromChainMeta)]
+//!   struct Sender {
+//!       id: u64,
+//!       member_name: String,
+//!       permission: Permission,
+//!   }
+//!
+//!   assert_eq!(Permission::Administrator.into_chain(), ChainMeta::Str("ADMINISTRATOR".to_string()));
+//!   ```
+//!
+//!   * 类型属性 `#[meta(...)]`，可以放在 struct 或 enum 的变体上
+//!     * `type = "FlashImage"` 指定 `type`，默认为类型名
+//!     * `type_alias = "..."` 加载时也接受的 `type`，可以多次使用，`type_eq` 同样接受
+//!     * `rename_all = "camelCase" | "snake_case" | "PascalCase" | "SCREAMING_SNAKE_CASE" | "none"` 字段名转换为键名的规则，默认 `camelCase`
+//!     * `crate = "my_bot::chain"` 生成的代码通过 `::msg_chain::...` 完整路径引用，`msg_chain` 被其他 crate 重新导出时使用该路径
+//!     * `serde` 同时实现 `Serialize`(与 `MessageChain` 一起，输出 `type`)与 `Deserialize`(与 `LoadFormMap` 一起，检查 `type`)，
+//!       具体的消息类型可以直接作为其他可序列化结构体的字段
+//!   * 字段属性 `#[meta(...)]`，`MessageChain` 与 `LoadFormMap` 使用相同的规则
+//!     * `rename = "key"` 使用指定的键名代替 camelCase 转换的键名
+//!     * `alias = "key"` 加载时也接受的键名，可以多次使用，用于不同 mirai-api-http 版本间改名的键
+//!     * `skip` 不参与读写，加载时使用 `Default::default()` (或者 `default` 指定的值)
+//!     * `default` / `default = "path"` 键不存在时使用 `Default::default()` / `path()`，而不是加载失败
+//!     * `numeric_strings` 该字段可以从字符串读取数字
+//!     * `strict` / `lenient` 该字段中的集合有元素加载失败时 报错 / 丢弃该元素，不设置时跟随 `LoadOptions`
+//! 
+//!   ```rust
+//!   # use msg_chain::*;
+//!   #[derive(MessageChain, LoadFormMap)]
+//!   struct File {
+//!       #[meta(rename = "file_id", alias = "id")]
+//!       file_id: String,
+//!       #[meta(default)]
+//!       size: u64,
+//!       #[meta(skip)]
+//!       downloaded: bool,
+//!   }
+//!   ```
+//! 
+//!   * 不支持的类型(`union`)，没有指定键名的 tuple struct 字段，错误的 `#[meta(...)]`，转换后重复的键名(`image_id` 与 `imageId`)，
+//!     以及消息中名为 `type` 的字段(与消息类型冲突，没有 `type` 的嵌套对象不受影响)都会在对应位置编译报错
+//!
+//!   ```rust,compile_fail
+//!   # use msg_chain::*;
+//!   #[derive(MessageChain, LoadFormMap)]
+//!   struct Image {
+//!       image_id: String,
+//!       // error: key `imageId` is already used by field `image_id`
+//!       #[meta(rename = "imageId")]
+//!       url: String,
+//!   }
+//!   ```
+//!
+//!   * `dyn MessageChain`(`Box<dyn MessageChain>`)实现了 `Serialize`，输出包含 `type`，
+//!     具体类型需要 `Serialize`/`Deserialize` 时使用类型属性 `#[meta(serde)]`
+//!
+//!   ```rust
+//!   # use msg_chain::*;
+//!   #[derive(MessageChain, LoadFormMap)]
+//!   #[meta(serde)]
+//!   struct Plain {
+//!       text: String,
+//!   }
+//!
+//!   let json = serde_json::to_string(&Plain { text: "好耶".to_string() }).unwrap();
+//!   assert_eq!(json, r#"{"type":"Plain","text":"好耶"}"#);
+//!   assert!(serde_json::from_str::<Plain>(r#"{"type":"Face","text":"好耶"}"#).is_err());
+//!   ```
+extern crate self as msg_chain;
+
+pub use chain_meta_limit_macro::chain_meta_limit;
+pub use error::{ChainError, ErrorKind, PathSeg};
+pub use field::Field;
+pub use from_chain_derive::{FromChainMeta, LoadFormMap};
+pub use impls::{ChainIndex, ChainKey};
+pub use indexmap::IndexMap;
+pub use msg_chain_derive::{IntoChainMeta, MessageChain};
+pub use options::LoadOptions;
+pub use raw::RawChain;
+pub use registry::{ChainRegistry, DuplicateType};
+pub use seq::{deserialize_chain, ChainFallback, ChainSeqSeed, FallbackPicker, UnknownPolicy};
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+pub mod error;
+pub mod field;
 pub mod impls;
+pub mod limit;
+#[cfg(feature = "mirai")]
+pub mod mirai;
+pub mod options;
+pub mod raw;
+pub mod registry;
+pub mod seq;
+
+#[doc(hidden)]
+pub mod __private {
+    use crate::{ChainError, ChainMap, ChainMeta, IndexMap, LoadFormMap, MessageChain};
+
+    #[cfg(feature = "regex")]
+    pub use regex;
+    pub use serde;
+
+    /// `Serialize` of a message, `type` is the first key
+    pub fn serialize_message<M, S>(chain: &M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        M: MessageChain + ?Sized,
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let all_data = chain.get_all();
+        let mut data = serializer.serialize_map(Some(all_data.len() + 1))?;
+        data.serialize_entry("type", chain.get_type())?;
+        for (k, v) in all_data.iter() {
+            data.serialize_entry(k, v)?;
+        }
+        data.end()
+    }
+
+    /// `Deserialize` of a message, a map with other `type` is an error
+    pub fn deserialize_message<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: LoadFormMap,
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde::Deserialize;
+
+        let map = IndexMap::<String, ChainMeta>::deserialize(deserializer)?;
+        T::try_load_from_ordered(map).map_err(D::Error::custom)
+    }
+
+    /// fields of a struct derived `FromChainMeta`
+    pub fn struct_fields(chain: Option<&ChainMeta>) -> Result<&ChainMap, ChainError> {
+        match chain {
+            Some(ChainMeta::Map(map)) => Ok(map),
+            Some(other) => Err(ChainError::mismatch("Map", other)),
+            None => Err(ChainError::missing()),
+        }
+    }
+}
 
 // data that contain in evry chain
 #[derive(Debug, PartialEq, Clone)]
@@ -116,10 +406,14 @@ pub enum ChainMeta {
     Bool(bool),
     Num(Number),
     SubChains(Vec<ChainMeta>),
-    Map(HashMap<&'static str, ChainMeta>),
-    MapOwn(HashMap<String, ChainMeta>),
+    /// keys keep the order they are inserted, or received
+    Map(ChainMap),
 }
 
+/// map inside `ChainMeta`, keys can be `&'static str` or `String` without copy,
+/// `collect` entries into `ChainMeta` also build a `Map`
+pub type ChainMap = IndexMap<Cow<'static, str>, ChainMeta>;
+
 // differnt type of number for chain meta
 #[derive(Debug, PartialEq, Clone)]
 pub enum Number {
@@ -130,49 +424,145 @@ pub enum Number {
 
 /// Message Chain
 pub trait MessageChain {
-    fn get_type(&self) -> &'static str;
+    fn get_type(&self) -> &str;
     fn get(&self, key: &str) -> Option<ChainMeta>;
-    fn all_keys(&self) -> Vec<&'static str>;
-    fn get_all(&self) -> Vec<(&'static str, ChainMeta)> {
+    fn all_keys(&self) -> Vec<&str>;
+    fn get_all(&self) -> Vec<(&str, ChainMeta)> {
         self.all_keys()
             .iter()
-            .map(|f| (*f, self.get(f).unwrap()))
+            .filter_map(|f| Some((*f, self.get(f)?)))
             .collect()
     }
 }
 
+/// Load Form Map:
+///  load a `MessageChain` from the received map,
+///  `try_load_from_map_with` is required, impls having only `load_from_map` need to move to it
 pub trait LoadFormMap: Sized + MessageChain {
-    fn load_from_map(map: &HashMap<String, ChainMeta>) -> Option<Self>;
+    fn try_load_from_map_with(
+        map: &HashMap<String, ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Self, ChainError>;
+    fn try_load_from_map(map: &HashMap<String, ChainMeta>) -> Result<Self, ChainError> {
+        Self::try_load_from_map_with(map, &LoadOptions::default())
+    }
+    fn load_from_map(map: &HashMap<String, ChainMeta>) -> Option<Self> {
+        Self::try_load_from_map(map).ok()
+    }
+    /// load from a map in received order, only types keeping the whole map (`RawChain`) need the order
+    fn try_load_from_ordered(map: IndexMap<String, ChainMeta>) -> Result<Self, ChainError> {
+        Self::try_load_from_map(&map.into_iter().collect())
+    }
     fn can_match(map: &HashMap<String, ChainMeta>) -> bool;
     fn type_eq(ty: &str) -> bool;
+    /// all the `type` can be loaded, empty if not known ahead, like `RawChain`
+    fn type_tags() -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+/// Chain Validate:
+///  check the limits of fields, implemented by `#[chain_meta_limit]`
+pub trait ChainValidate {
+    fn validate(&self) -> Result<(), ChainError>;
 }
 
 /// into Chain Meta:
 ///  transform SELF into Chain meta
 pub trait IntoChainMeta {
+    #[allow(clippy::wrong_self_convention)]
     fn into_chain(&self) -> ChainMeta;
+
+    /// the value of a field in `MessageChain`, `None` means the key should be omitted
+    #[allow(clippy::wrong_self_convention)]
+    fn into_chain_field(&self) -> Option<ChainMeta> {
+        Some(self.into_chain())
+    }
 }
 /// into Chain Meta:
-///  transform a ChainMeta into Self if Possable
+///  transform a ChainMeta into Self if Possable,
+///  `try_from_chain` is required, impls having only `from_chain` need to move to it
 pub trait FromChainMeta: Sized {
-    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self>;
+    fn try_from_chain(chain: Option<&ChainMeta>) -> Result<Self, ChainError>;
+    /// load with options, types contain other `FromChainMeta` should pass `options` down
+    fn try_from_chain_with(
+        chain: Option<&ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Self, ChainError> {
+        let _ = options;
+        Self::try_from_chain(chain)
+    }
+    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
+        Self::try_from_chain(chain).ok()
+    }
 }
 
 
 #[macro_export]
 macro_rules! msg_loader_generate {
+    (@load $map:ident, $options:ident, [$( $x:ty ),*] [$( $fallback:ty )?]) => {{
+        $(
+            if <$x as $crate::LoadFormMap>::can_match($map){
+                return ::std::result::Result::Ok(::std::boxed::Box::new(<$x as $crate::LoadFormMap>::try_load_from_map_with($map, $options)?));
+            }
+        )*
+        $(
+            if <$fallback as $crate::LoadFormMap>::can_match($map){
+                return ::std::result::Result::Ok(::std::boxed::Box::new(<$fallback as $crate::LoadFormMap>::try_load_from_map_with($map, $options)?));
+            }
+        )?
 
-    
-    ( $( $x:ty ),* ) => {
-        pub fn message_chain_loader(map: &HashMap<String, ChainMeta>)->Option<Box<dyn MessageChain>>{
-            $(
-                if <$x>::can_match(map){
-                    return Some(Box::new(<$x>::load_from_map(map)?));
-                }
-            )*
+        ::std::result::Result::Err($crate::ChainError::unmatched($map))
+    }};
+    // listing a type twice gives "conflicting implementations of trait `MessageTypeListedTwice`"
+    (@unique [$( $x:ty ),*] [$( $fallback:ty )?]) => {
+        const _: () = {
+            #[allow(dead_code)]
+            trait MessageTypeListedTwice {}
+            $( impl MessageTypeListedTwice for $x {} )*
+            $( impl MessageTypeListedTwice for $fallback {} )?
+        };
+    };
+    // only the plain loader, `_with(options)` and `Option` versions need a name for each
+    ( $vis:vis fn $name:ident => $( $x:ty ),* $( ; fallback = $fallback:ty )? ) => {
+        $vis fn $name(map: &::std::collections::HashMap<::std::string::String, $crate::ChainMeta>)->::std::result::Result<::std::boxed::Box<dyn $crate::MessageChain>, $crate::ChainError>{
+            let options = &$crate::LoadOptions::default();
+            $crate::msg_loader_generate!(@load map, options, [$( $x ),*] [$( $fallback )?])
+        }
+
+        $crate::msg_loader_generate!(@unique [$( $x ),*] [$( $fallback )?]);
+    };
+    ( $( $x:ty ),* $( ; fallback = $fallback:ty )? ) => {
+        pub fn try_message_chain_loader_with(map: &::std::collections::HashMap<::std::string::String, $crate::ChainMeta>, options: &$crate::LoadOptions)->::std::result::Result<::std::boxed::Box<dyn $crate::MessageChain>, $crate::ChainError>{
+            $crate::msg_loader_generate!(@load map, options, [$( $x ),*] [$( $fallback )?])
+        }
 
-            return None
+        pub fn try_message_chain_loader(map: &::std::collections::HashMap<::std::string::String, $crate::ChainMeta>)->::std::result::Result<::std::boxed::Box<dyn $crate::MessageChain>, $crate::ChainError>{
+            try_message_chain_loader_with(map, &$crate::LoadOptions::default())
         }
+
+        pub fn message_chain_loader(map: &::std::collections::HashMap<::std::string::String, $crate::ChainMeta>)->::std::option::Option<::std::boxed::Box<dyn $crate::MessageChain>>{
+            try_message_chain_loader(map).ok()
+        }
+
+        pub fn deserialize_message_chain<'de, D>(deserializer: D, policy: $crate::UnknownPolicy)->::std::result::Result<::std::vec::Vec<::std::boxed::Box<dyn $crate::MessageChain>>, D::Error>
+        where
+            D: $crate::__private::serde::Deserializer<'de>,
+        {
+            // the fallback is given the map in received order, not through `try_message_chain_loader`
+            fn known(map: &::std::collections::HashMap<::std::string::String, $crate::ChainMeta>)->::std::result::Result<::std::boxed::Box<dyn $crate::MessageChain>, $crate::ChainError>{
+                let options = &$crate::LoadOptions::default();
+                $crate::msg_loader_generate!(@load map, options, [$( $x ),*] [])
+            }
+            let seed = $crate::ChainSeqSeed::new(known, policy)
+                $( .fallback(|map| {
+                    <$fallback as $crate::LoadFormMap>::can_match(map)
+                        .then_some($crate::seq::load_ordered::<$fallback> as $crate::ChainFallback)
+                }) )?;
+            $crate::__private::serde::de::DeserializeSeed::deserialize(seed, deserializer)
+        }
+
+        $crate::msg_loader_generate!(@unique [$( $x ),*] [$( $fallback )?]);
     };
 }
 
@@ -279,6 +669,50 @@ mod test {
         assert_eq!(None, res)
     }
 
+    #[test]
+    fn test_try_load_error() {
+        let map: HashMap<String, ChainMeta> = HashMap::new();
+        let err = Plain::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::MissingField);
+        assert_eq!(err.path_string(), "type");
+
+        let map = map_generate!(Plain=>[text: 11_u64]);
+        let err = Plain::try_load_from_map(&map).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::TypeMismatch {
+                expected: "Str",
+                actual: "Num(N)"
+            }
+        );
+        assert_eq!(err.path_string(), "text");
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Face {
+        face_id: u8,
+    }
+
+    #[test]
+    fn test_try_load_camel_key() {
+        let map = map_generate!(Face=>[faceId: 300_u64]);
+        let err = Face::try_load_from_map(&map).unwrap_err();
+
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::Overflow {
+                target: "u8",
+                value: Number::N(300)
+            }
+        );
+        assert_eq!(err.path(), &[PathSeg::Key("faceId".to_string())]);
+        assert_eq!(err.to_string(), "`faceId`: number N(300) out of range for u8");
+
+        let map = map_generate!(Face=>[]);
+        let err = Face::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.to_string(), "`faceId`: missing field");
+    }
+
     #[derive(MessageChain, LoadFormMap, PartialEq, Debug)]
     struct AtAll;
 
@@ -316,6 +750,285 @@ mod test {
         assert_eq!(None, res)
     }
 
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    enum Segment {
+        Plain(Plain),
+        Image(Image),
+        Dice { value: u8, is_cheat: bool },
+        AtAll,
+    }
+
+    #[test]
+    fn test_enum() {
+        let seg = Segment::Dice {
+            value: 6,
+            is_cheat: false,
+        };
+        assert_eq!(seg.get_type(), "Dice");
+        assert_eq!(seg.get("isCheat"), Some(ChainMeta::Bool(false)));
+        assert_eq!(seg.all_keys(), vec!["value", "isCheat"]);
+
+        let seg = Segment::Plain(Plain {
+            text: Some("Rust NB".to_string()),
+        });
+        assert_eq!(seg.get_type(), "Plain");
+        assert_eq!(seg.get("text"), Some(ChainMeta::Str("Rust NB".to_string())));
+        assert_eq!(Segment::AtAll.get_type(), "AtAll");
+    }
+
+    #[test]
+    fn test_from_enum() {
+        let map = map_generate!(Plain=>[text: "好耶"]);
+        assert_eq!(
+            Segment::load_from_map(&map),
+            Some(Segment::Plain(Plain {
+                text: Some("好耶".to_string())
+            }))
+        );
+
+        let map = map_generate!(Dice=>[value: 3_u64, isCheat: true]);
+        assert_eq!(
+            Segment::load_from_map(&map),
+            Some(Segment::Dice {
+                value: 3,
+                is_cheat: true
+            })
+        );
+
+        let map = map_generate!(AtAll=>[]);
+        assert_eq!(Segment::load_from_map(&map), Some(Segment::AtAll));
+
+        let map = map_generate!(Dice=>[value: 3_u64]);
+        let err = Segment::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.to_string(), "`isCheat`: missing field");
+
+        let map = map_generate!(Face=>[faceId: 1_u64]);
+        let err = Segment::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::UnknownType("Face".to_string()));
+
+        assert!(Segment::type_eq("Image"));
+        assert!(!Segment::type_eq("Face"));
+    }
+
+    fn default_size() -> u64 {
+        1024
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct File {
+        #[meta(rename = "file_id", alias = "id")]
+        file_id: String,
+        #[meta(default)]
+        name: String,
+        #[meta(default = "default_size")]
+        size: u64,
+        #[meta(skip)]
+        downloaded: bool,
+    }
+
+    #[test]
+    fn test_field_attrs() {
+        let file = File {
+            file_id: "/abc".to_string(),
+            name: "a.txt".to_string(),
+            size: 12,
+            downloaded: true,
+        };
+        assert_eq!(file.all_keys(), vec!["file_id", "name", "size"]);
+        assert_eq!(file.get("file_id"), Some(ChainMeta::Str("/abc".to_string())));
+        assert_eq!(file.get("fileId"), None);
+        assert_eq!(file.get("downloaded"), None);
+
+        let map = map_generate!(&file);
+        assert_eq!(
+            File::load_from_map(&map),
+            Some(File {
+                downloaded: false,
+                ..file
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_field_attrs() {
+        let map = map_generate!(File=>[id: "/abc"]);
+        assert_eq!(
+            File::load_from_map(&map),
+            Some(File {
+                file_id: "/abc".to_string(),
+                name: String::new(),
+                size: 1024,
+                downloaded: false,
+            })
+        );
+
+        let map = map_generate!(File=>[id: 1_u64]);
+        let err = File::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.path_string(), "id");
+
+        let map = map_generate!(File=>[size: 1_u64]);
+        let err = File::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.to_string(), "`file_id`: missing field");
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    #[meta(type = "FlashImage", type_alias = "Image", rename_all = "snake_case")]
+    struct AnyImage {
+        image_id: Option<String>,
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    #[meta(rename_all = "PascalCase")]
+    enum Event {
+        #[meta(type = "MemberJoin", type_alias = "MemberJoinEvent")]
+        Join { member_id: u64 },
+        #[meta(rename_all = "none")]
+        Leave { member_id: u64 },
+    }
+
+    #[test]
+    fn test_container_attrs() {
+        let img = AnyImage {
+            image_id: Some("/abc".to_string()),
+        };
+        assert_eq!(img.get_type(), "FlashImage");
+        assert_eq!(img.all_keys(), vec!["image_id"]);
+        assert!(AnyImage::type_eq("FlashImage"));
+        assert!(AnyImage::type_eq("Image"));
+        assert!(!AnyImage::type_eq("AnyImage"));
+
+        let map = map_generate!(Image=>[image_id: "/abc"]);
+        assert_eq!(AnyImage::load_from_map(&map), Some(img));
+
+        let join = Event::Join { member_id: 1 };
+        assert_eq!(join.get_type(), "MemberJoin");
+        assert_eq!(join.all_keys(), vec!["MemberId"]);
+        assert_eq!(Event::Leave { member_id: 1 }.all_keys(), vec!["member_id"]);
+
+        let map = map_generate!(MemberJoinEvent=>[MemberId: 1_u64]);
+        assert_eq!(Event::load_from_map(&map), Some(join));
+        let map = map_generate!(Leave=>[member_id: 2_u64]);
+        assert_eq!(Event::load_from_map(&map), Some(Event::Leave { member_id: 2 }));
+    }
+
+    #[chain_meta_limit]
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Music {
+        #[limit(len(max = 8), non_empty)]
+        title: String,
+        #[limit(range(1..=6))]
+        level: u8,
+        #[limit(one_of("QQMusic", "NeteaseCloudMusic"))]
+        #[meta(rename = "type_of")]
+        kind: Option<String>,
+    }
+
+    #[test]
+    fn test_limit_validate() {
+        let mut music = Music {
+            title: "好耶".to_string(),
+            level: 3,
+            kind: None,
+        };
+        assert_eq!(music.validate(), Ok(()));
+
+        music.title = String::new();
+        assert_eq!(
+            music.validate().unwrap_err().to_string(),
+            "`title`: invalid value, should not be empty"
+        );
+
+        music.title = "Never Gonna Give You Up".to_string();
+        assert_eq!(
+            music.validate().unwrap_err().to_string(),
+            "`title`: invalid value, length 23 is greater than max 8"
+        );
+
+        music.title = "好耶".to_string();
+        music.kind = Some("KugouMusic".to_string());
+        let err = music.validate().unwrap_err();
+        assert_eq!(err.path_string(), "type_of");
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::Invalid(
+                r#""KugouMusic" is not one of ["QQMusic", "NeteaseCloudMusic"]"#.to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_limit_load() {
+        let map = map_generate!(Music=>[title: "好耶", level: 3_u64, type_of: "QQMusic"]);
+        assert!(Music::load_from_map(&map).is_some());
+
+        let map = map_generate!(Music=>[title: "好耶", level: 7_u64]);
+        let err = Music::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.to_string(), "`level`: invalid value, 7 out of range 1..=6");
+    }
+
+    #[chain_meta_limit]
+    #[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq)]
+    struct Level(#[limit(range(1..=6))] u8);
+
+    #[test]
+    fn test_limit_newtype() {
+        assert_eq!(Level::try_from_chain(Some(&3_u8.into_chain())), Ok(Level(3)));
+
+        let err = Level::try_from_chain(Some(&7_u8.into_chain())).unwrap_err();
+        assert_eq!(err, Level(7).validate().unwrap_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[chain_meta_limit]
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Code {
+        #[limit(regex = "^[0-9]+$")]
+        code: String,
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_limit_regex() {
+        let code = Code {
+            code: "114514".to_string(),
+        };
+        assert_eq!(code.validate(), Ok(()));
+
+        let map = map_generate!(Code=>[code: "11a"]);
+        let err = Code::try_load_from_map(&map).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"`code`: invalid value, "11a" does not match /^[0-9]+$/"#
+        );
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct At {
+        #[meta(numeric_strings)]
+        target: u64,
+        display: Option<String>,
+    }
+
+    #[test]
+    fn test_numeric_strings_field() {
+        let map = map_generate!(At=>[target: "1141451919", display: "@QQ"]);
+        assert_eq!(
+            At::load_from_map(&map),
+            Some(At {
+                target: 1141451919,
+                display: Some("@QQ".to_string())
+            })
+        );
+
+        let map = map_generate!(Face=>[faceId: "12"]);
+        assert!(Face::load_from_map(&map).is_none());
+        let options = LoadOptions::new().numeric_strings(true);
+        assert_eq!(
+            Face::try_load_from_map_with(&map, &options),
+            Ok(Face { face_id: 12 })
+        );
+    }
+
     msg_loader_generate!(Plain, AtAll, Image);
 
     #[test]
@@ -330,6 +1043,92 @@ mod test {
         assert_eq!(pla, res);
     }
 
+    #[test]
+    fn test_try_msg_chain_loader() {
+        let map = map_generate!(Face=>[faceId: 1_u64]);
+        let err = try_message_chain_loader(&map).err().unwrap();
+        assert_eq!(err.kind(), &ErrorKind::UnknownType("Face".to_string()));
+
+        let pla = Plain { text: None };
+        let res = try_message_chain_loader(&map_generate!(&pla)).unwrap();
+        let err = res.try_into_target::<AtAll>().unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::UnknownType("Plain".to_string()));
+        assert_eq!(res.try_into_target::<Plain>(), Ok(pla));
+    }
+
+    #[test]
+    fn test_deserialize_message_chain() {
+        let json = r#"[
+            {"type": "Plain", "text": "好耶"},
+            {"type": "Face", "faceId": 1, "name": "惊讶"},
+            {"type": "AtAll"}
+        ]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let res = deserialize_message_chain(&mut de, UnknownPolicy::Skip).unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(
+            res[0].into_target::<Plain>(),
+            Some(Plain {
+                text: Some("好耶".to_string())
+            })
+        );
+        assert_eq!(res[1].into_target::<AtAll>(), Some(AtAll));
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let res = deserialize_message_chain(&mut de, UnknownPolicy::Raw).unwrap();
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[1].get_type(), "Face");
+        assert_eq!(res[1].get("name"), Some(ChainMeta::Str("惊讶".to_string())));
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let err = deserialize_message_chain(&mut de, UnknownPolicy::Error).err().unwrap();
+        assert!(err.to_string().contains("`[1]`: unsupported message type `Face`"));
+    }
+
+    #[test]
+    fn test_deserialize_message_chain_bad_element() {
+        let json = r#"[{"type": "Plain", "text": 1}]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let err = deserialize_message_chain(&mut de, UnknownPolicy::Skip).err().unwrap();
+        assert!(err.to_string().contains("`[0].text`: expected Str, found Num(N)"));
+    }
+
+    #[test]
+    fn test_raw_policy_order() {
+        let json = r#"[{"type":"ShortVideo","videoId":"v1","size":-1,"a":1}]"#;
+        let mut de = serde_json::Deserializer::from_str(json);
+        let chains = deserialize_message_chain(&mut de, UnknownPolicy::Raw).unwrap();
+        assert_eq!(serde_json::to_string(&chains).unwrap(), json);
+    }
+
+    #[test]
+    fn test_enum_into_target() {
+        let map = map_generate!(AtAll=>[]);
+
+        let res = message_chain_loader(&map).unwrap();
+        match res.into_target::<Segment>().unwrap() {
+            Segment::AtAll => {}
+            _ => panic!("should be AtAll"),
+        }
+    }
+
+    #[test]
+    fn test_enum_load_with() {
+        let map = map_generate!(Dice=>[value: "3", isCheat: false]);
+        assert!(Segment::load_from_map(&map).is_none());
+
+        let options = LoadOptions::new().numeric_strings(true);
+        assert_eq!(
+            Segment::try_load_from_map_with(&map, &options),
+            Ok(Segment::Dice {
+                value: 3,
+                is_cheat: false
+            })
+        );
+    }
+
     #[test]
     fn test_option_items() {
         let map: HashMap<String, ChainMeta> = map_generate!(
@@ -351,4 +1150,413 @@ mod test {
             base64:None
         })
     }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Forward {
+        node_list: Vec<u64>,
+        #[meta(lenient)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_strict_items() {
+        let nodes = ChainMeta::SubChains(vec![1_u64.into_chain(), "bad".into_chain()]);
+        let tags = ChainMeta::SubChains(vec!["a".into_chain(), 2_u64.into_chain()]);
+        let map = map_generate!(Forward=>[nodeList: nodes.clone(), tags: tags.clone()]);
+
+        let err = Forward::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.path_string(), "nodeList[1]");
+
+        let options = LoadOptions::new().lenient(true);
+        assert_eq!(
+            Forward::try_load_from_map_with(&map, &options),
+            Ok(Forward {
+                node_list: vec![1],
+                tags: vec!["a".to_string()]
+            })
+        );
+
+        let map = map_generate!(Forward=>[nodeList: Vec::<u64>::new(), tags: tags]);
+        assert_eq!(
+            Forward::try_load_from_map(&map),
+            Ok(Forward {
+                node_list: vec![],
+                tags: vec!["a".to_string()]
+            })
+        );
+
+        let extra: ChainMeta = vec![("ok".to_string(), 1_u8.into_chain()), ("bad".to_string(), nodes)]
+            .into_iter()
+            .collect();
+        let err = extra.try_into_target::<HashMap<String, u8>>().unwrap_err();
+        assert_eq!(err.path(), &[PathSeg::Key("bad".to_string())]);
+        assert_eq!(
+            HashMap::<String, u8>::try_from_chain_with(Some(&extra), &options).map(|m| m.len()),
+            Ok(1)
+        );
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct MemberPatch {
+        name: Field<String>,
+        special_title: Field<String>,
+        level: Field<u8>,
+    }
+
+    #[test]
+    fn test_field_missing_null() {
+        let map = map_generate!(MemberPatch=>[name: "好耶", specialTitle: Option::<String>::None]);
+        let patch = MemberPatch::try_load_from_map(&map).unwrap();
+        assert_eq!(
+            patch,
+            MemberPatch {
+                name: Field::Value("好耶".to_string()),
+                special_title: Field::Null,
+                level: Field::Missing,
+            }
+        );
+
+        assert_eq!(patch.all_keys(), vec!["name", "specialTitle", "level"]);
+        assert_eq!(patch.get("level"), None);
+        assert_eq!(
+            patch.get_all(),
+            vec![
+                ("name", "好耶".into_chain()),
+                ("specialTitle", ChainMeta::Null)
+            ]
+        );
+        assert_eq!(map_generate!(&patch), map);
+    }
+
+    mod fallback {
+        use super::*;
+        msg_loader_generate!(Plain, AtAll; fallback = RawChain);
+    }
+
+    #[test]
+    fn test_raw_fallback() {
+        let json = r#"[{"type":"Plain","text":"好耶"},{"type":"ShortVideo","videoId":"v1","size":-1,"tags":["a",null]}]"#;
+        let mut de = serde_json::Deserializer::from_str(json);
+        let chains = fallback::deserialize_message_chain(&mut de, UnknownPolicy::Error).unwrap();
+
+        assert_eq!(chains[0].get_type(), "Plain");
+        assert_eq!(chains[1].get_type(), "ShortVideo");
+        assert_eq!(chains[1].get("videoId"), Some("v1".into_chain()));
+
+        let back: serde_json::Value = serde_json::to_value(&chains).unwrap();
+        assert_eq!(back, serde_json::from_str::<serde_json::Value>(json).unwrap());
+        // keys of `RawChain` are in received order, `type` always the first
+        assert_eq!(serde_json::to_string(&chains).unwrap(), json);
+
+        let raw = chains[0].try_into_target::<RawChain>().unwrap();
+        assert_eq!(raw.get_type(), "Plain");
+        assert_eq!(raw.data().get("text"), Some(&"好耶".into_chain()));
+
+        let map = map_generate!["text": "no type"];
+        assert!(fallback::message_chain_loader(&map).is_none());
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    #[meta(type = "Plain", type_alias = "Text")]
+    struct RichPlain {
+        text: String,
+        #[meta(default)]
+        bold: bool,
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut tags = Segment::type_tags();
+        tags.sort_unstable();
+        assert_eq!(tags, vec!["AtAll", "Dice", "Image", "Plain"]);
+        assert_eq!(RichPlain::type_tags(), vec!["Plain", "Text"]);
+
+        let mut base = ChainRegistry::new();
+        base.register::<Plain>()
+            .unwrap()
+            .register::<Image>()
+            .unwrap()
+            .register::<Plain>()
+            .unwrap();
+        assert!(base.contains("Image"));
+        assert!(!base.contains("Text"));
+
+        let err = base.register::<Segment>().unwrap_err();
+        assert_eq!(err.ty, "Plain");
+        assert!(!base.contains("Dice"));
+
+        let plain = map_generate!(Plain=>[text: "好耶"]);
+        assert_eq!(base.load(&plain).unwrap().get("bold"), None);
+        let dice = map_generate!(Dice=>[value: 1_u8]);
+        assert_eq!(
+            base.try_load(&dice).err().map(|e| e.kind().clone()),
+            Some(ErrorKind::UnknownType("Dice".to_string()))
+        );
+
+        let mut plugin = ChainRegistry::new();
+        plugin.register::<RichPlain>().unwrap().register::<RawChain>().unwrap();
+        assert!(base.clone().merge(&plugin).is_err());
+
+        base.layer(&plugin);
+        assert_eq!(base.load(&plain).unwrap().get("bold"), Some(false.into_chain()));
+        assert_eq!(base.load(&dice).unwrap().get_type(), "Dice");
+
+        let mut de = serde_json::Deserializer::from_str(r#"[{"type":"Text","text":"a"}]"#);
+        let chains = base.deserialize(&mut de, UnknownPolicy::Error).unwrap();
+        assert_eq!(chains[0].get_type(), "Plain");
+    }
+
+    #[test]
+    fn test_registry_raw_fallback() {
+        let json = r#"[{"type":"Plain","text":"好耶"},{"type":"ShortVideo","videoId":"v1","size":-1,"a":1}]"#;
+        let mut registry = ChainRegistry::new();
+        registry.register::<Plain>().unwrap();
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let chains = registry.deserialize(&mut de, UnknownPolicy::Skip).unwrap();
+        assert_eq!(chains.len(), 1);
+
+        registry.register::<RawChain>().unwrap();
+        let mut de = serde_json::Deserializer::from_str(json);
+        let chains = registry.deserialize(&mut de, UnknownPolicy::Error).unwrap();
+        assert!(chains[0].try_into_target::<Plain>().is_ok());
+        // forwarded as received, not sorted like `RawChain::from_map`
+        assert_eq!(serde_json::to_string(&chains).unwrap(), json);
+    }
+
+    mod named {
+        msg_loader_generate!(pub fn incoming_loader => super::Plain, super::AtAll);
+        msg_loader_generate!(pub(crate) fn image_loader => super::Image; fallback = crate::RawChain);
+    }
+
+    #[test]
+    fn test_named_loader() {
+        let plain = map_generate!(Plain=>[text: "好耶"]);
+        let image = map_generate!(Image=>[imageId: "{01E9451B-70ED-EAE3-B37C-101F1EEBF5B5}.mirai"]);
+
+        assert_eq!(named::incoming_loader(&plain).unwrap().get_type(), "Plain");
+        assert_eq!(
+            named::incoming_loader(&image).err().map(|e| e.kind().clone()),
+            Some(ErrorKind::UnknownType("Image".to_string()))
+        );
+        assert!(named::image_loader(&image).unwrap().into_target::<Image>().is_some());
+        assert!(named::image_loader(&plain).unwrap().into_target::<RawChain>().is_some());
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Wrapper<'a, T, const N: usize>
+    where
+        T: Clone,
+    {
+        items: [T; N],
+        label: std::borrow::Cow<'a, str>,
+        #[meta(skip)]
+        cache: Vec<T>,
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    enum Either<A, B> {
+        Left(A),
+        Right(B),
+        Custom { value: u8 },
+    }
+
+    #[test]
+    fn test_generics() {
+        let wrapper: Wrapper<'_, u8, 2> = Wrapper {
+            items: [1, 2],
+            label: "好耶".into(),
+            cache: vec![],
+        };
+        let map = map_generate!(&wrapper);
+        assert_eq!(map.get("type"), Some(&"Wrapper".into_chain()));
+        assert_eq!(Wrapper::<u8, 2>::try_load_from_map(&map), Ok(wrapper));
+        assert!(Wrapper::<u8, 3>::try_load_from_map(&map).is_err());
+
+        let map = map_generate!(Plain=>[text: "好耶"]);
+        let either = Either::<Plain, AtAll>::try_load_from_map(&map).unwrap();
+        assert_eq!(
+            either,
+            Either::Left(Plain {
+                text: Some("好耶".to_string())
+            })
+        );
+        assert_eq!(either.get_type(), "Plain");
+        let mut tags = Either::<Plain, AtAll>::type_tags();
+        tags.sort_unstable();
+        assert_eq!(tags, vec!["AtAll", "Custom", "Plain"]);
+    }
+
+    #[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq, Clone, Copy)]
+    struct GroupId(u64);
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Mute {
+        group_id: GroupId,
+        member: Option<GroupId>,
+    }
+
+    #[chain_meta_limit]
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    struct Dice(
+        #[meta(rename = "value")]
+        #[limit(range(1..=6))]
+        u8,
+    );
+
+    #[test]
+    fn test_newtype() {
+        assert_eq!(GroupId(1).into_chain(), 1_u64.into_chain());
+
+        let mute = Mute {
+            group_id: GroupId(114514),
+            member: None,
+        };
+        let map = map_generate!(&mute);
+        assert_eq!(map.get("groupId"), Some(&ChainMeta::Num(Number::N(114514))));
+        assert_eq!(Mute::try_load_from_map(&map), Ok(mute));
+
+        let map = map_generate!(Mute=>[groupId: "1"]);
+        let err = Mute::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.to_string(), "`groupId`: expected Num, found Str");
+    }
+
+    #[test]
+    fn test_tuple_struct() {
+        let dice = Dice(4);
+        assert_eq!(dice.get_type(), "Dice");
+        assert_eq!(dice.get_all(), vec![("value", 4_u8.into_chain())]);
+
+        let map = map_generate!(Dice=>[value: 4_u8]);
+        assert_eq!(Dice::try_load_from_map(&map), Ok(dice));
+
+        let map = map_generate!(Dice=>[value: 7_u8]);
+        let err = Dice::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.path_string(), "value");
+    }
+
+    #[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq, Clone, Copy)]
+    #[meta(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum Permission {
+        Owner,
+        Administrator,
+        #[meta(alias = "NORMAL")]
+        Member,
+    }
+
+    #[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq)]
+    struct Sender {
+        id: GroupId,
+        member_name: String,
+        permission: Permission,
+        #[meta(default)]
+        special_title: Field<String>,
+    }
+
+    #[test]
+    fn test_untagged_struct() {
+        assert_eq!(
+            Permission::Administrator.into_chain(),
+            ChainMeta::Str("ADMINISTRATOR".to_string())
+        );
+        assert_eq!(
+            Permission::try_from_chain(Some(&"NORMAL".into_chain())),
+            Ok(Permission::Member)
+        );
+        let err = Permission::try_from_chain(Some(&"GUEST".into_chain())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value, unknown value `GUEST`, expected one of `OWNER`, `ADMINISTRATOR`, `MEMBER`"
+        );
+
+        let sender = Sender {
+            id: GroupId(1),
+            member_name: "a".to_string(),
+            permission: Permission::Owner,
+            special_title: Field::Missing,
+        };
+        let chain = sender.into_chain();
+        match &chain {
+            ChainMeta::Map(map) => {
+                assert_eq!(map.get("type"), None);
+                assert_eq!(map.get("specialTitle"), None);
+                assert_eq!(map.get("permission"), Some(&"OWNER".into_chain()));
+            }
+            other => panic!("expected Map, found {:?}", other),
+        }
+        assert_eq!(chain.try_into_target(), Ok(sender));
+
+        let map: ChainMeta = vec![
+            ("id", 2_u64.into_chain()),
+            ("memberName", "b".into_chain()),
+            ("permission", "ADMIN".into_chain()),
+        ]
+        .into_iter()
+        .collect();
+        let err = map.try_into_target::<Sender>().unwrap_err();
+        assert_eq!(err.path_string(), "permission");
+    }
+
+    /// untagged maps have no tag, `type` is a normal key
+    #[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq)]
+    struct Member {
+        id: u64,
+        r#type: String,
+    }
+
+    #[test]
+    fn test_untagged_type_key() {
+        let member = Member {
+            id: 1,
+            r#type: "Friend".to_string(),
+        };
+        let chain = member.into_chain();
+        assert_eq!(chain["type"], "Friend".into_chain());
+        assert_eq!(chain.try_into_target(), Ok(member));
+    }
+
+    #[test]
+    fn test_derive_convert() {
+        use std::convert::TryFrom;
+
+        let chain = ChainMeta::from(Permission::Member);
+        assert_eq!(chain.as_str(), Some("MEMBER"));
+        assert_eq!(Permission::try_from(chain), Ok(Permission::Member));
+        assert_eq!(ChainMeta::from(GroupId(7)).as_u64(), Some(7));
+        assert!(GroupId::try_from(ChainMeta::from("7")).is_err());
+    }
+
+    #[derive(MessageChain, LoadFormMap, Debug, PartialEq)]
+    #[meta(serde, type = "Plain")]
+    struct Reply {
+        text: String,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SendMessage {
+        target: u64,
+        message_chain: Vec<Reply>,
+    }
+
+    #[test]
+    fn test_serde_attr() {
+        let send = SendMessage {
+            target: 1,
+            message_chain: vec![Reply {
+                text: "好耶".to_string(),
+            }],
+        };
+        let json = serde_json::to_string(&send).unwrap();
+        assert_eq!(
+            json,
+            r#"{"target":1,"messageChain":[{"type":"Plain","text":"好耶"}]}"#
+        );
+
+        let send: SendMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(send.message_chain[0].text, "好耶");
+
+        let err = serde_json::from_str::<Reply>(r#"{"type":"Image","text":"好耶"}"#).unwrap_err();
+        assert_eq!(err.to_string(), "unsupported message type `Image`");
+    }
 }
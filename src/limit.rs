@@ -0,0 +1,102 @@
+//! checks used by the code `#[chain_meta_limit]` generated
+//!
+//! every check return the reason when the value break the limit
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::ops::RangeBounds;
+
+/// value that has a length
+pub trait HasLen {
+    fn limit_len(&self) -> usize;
+}
+
+impl HasLen for str {
+    fn limit_len(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl HasLen for String {
+    fn limit_len(&self) -> usize {
+        self.as_str().limit_len()
+    }
+}
+
+impl<T> HasLen for [T] {
+    fn limit_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLen for Vec<T> {
+    fn limit_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> HasLen for HashMap<K, V> {
+    fn limit_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> HasLen for BTreeMap<K, V> {
+    fn limit_len(&self) -> usize {
+        self.len()
+    }
+}
+
+/// `len(min = .., max = ..)`
+pub fn len<T: HasLen + ?Sized>(v: &T, min: Option<usize>, max: Option<usize>) -> Result<(), String> {
+    let l = v.limit_len();
+    match (min, max) {
+        (Some(min), _) if l < min => Err(format!("length {} is less than min {}", l, min)),
+        (_, Some(max)) if l > max => Err(format!("length {} is greater than max {}", l, max)),
+        _ => Ok(()),
+    }
+}
+
+/// `non_empty`
+pub fn non_empty<T: HasLen + ?Sized>(v: &T) -> Result<(), String> {
+    if v.limit_len() == 0 {
+        Err("should not be empty".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// `range(1..=10000)`
+pub fn range<T, R>(v: &T, range: R) -> Result<(), String>
+where
+    T: PartialOrd + Debug,
+    R: RangeBounds<T> + Debug,
+{
+    if range.contains(v) {
+        Ok(())
+    } else {
+        Err(format!("{:?} out of range {:?}", v, range))
+    }
+}
+
+/// `one_of("a", "b")`
+pub fn one_of<T, O>(v: &T, options: &[O]) -> Result<(), String>
+where
+    T: PartialEq<O> + Debug + ?Sized,
+    O: Debug,
+{
+    if options.iter().any(|o| v == o) {
+        Ok(())
+    } else {
+        Err(format!("{:?} is not one of {:?}", v, options))
+    }
+}
+
+/// `regex = "..."`
+#[cfg(feature = "regex")]
+pub fn regex<T: AsRef<str> + ?Sized>(v: &T, re: &regex::Regex) -> Result<(), String> {
+    if re.is_match(v.as_ref()) {
+        Ok(())
+    } else {
+        Err(format!("{:?} does not match /{}/", v.as_ref(), re.as_str()))
+    }
+}
@@ -0,0 +1,298 @@
+//! mirai-api-http 的全部消息类型
+//!
+//! 需要开启 feature `mirai`，`message_chain_loader` 可以加载以下所有类型
+use crate::{ChainMeta, FromChainMeta, IntoChainMeta, LoadFormMap, MessageChain};
+
+crate::msg_loader_generate!(
+    Source,
+    Quote,
+    At,
+    AtAll,
+    Face,
+    Plain,
+    Image,
+    FlashImage,
+    Voice,
+    Xml,
+    Json,
+    App,
+    Poke,
+    Dice,
+    MarketFace,
+    MusicShare,
+    Forward,
+    File,
+    MiraiCode
+);
+
+/// 消息源，永远为 messageChain 的第一个元素
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Source {
+    pub id: i32,
+    pub time: i64,
+}
+
+/// 引用回复
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Quote {
+    pub id: i32,
+    pub group_id: u64,
+    pub sender_id: u64,
+    pub target_id: u64,
+    pub origin: Vec<ChainMeta>,
+}
+
+/// @某人
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct At {
+    pub target: u64,
+    pub display: Option<String>,
+}
+
+/// @全体成员
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct AtAll;
+
+/// QQ表情，`face_id` 与 `name` 至少有一个
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Face {
+    pub face_id: Option<u32>,
+    pub name: Option<String>,
+}
+
+/// 文字
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Plain {
+    pub text: String,
+}
+
+/// 图片
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Image {
+    pub image_id: Option<String>,
+    pub url: Option<String>,
+    pub path: Option<String>,
+    pub base64: Option<String>,
+}
+
+/// 闪照
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct FlashImage {
+    pub image_id: Option<String>,
+    pub url: Option<String>,
+    pub path: Option<String>,
+    pub base64: Option<String>,
+}
+
+/// 语音
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Voice {
+    pub voice_id: Option<String>,
+    pub url: Option<String>,
+    pub path: Option<String>,
+    pub base64: Option<String>,
+    pub length: Option<u64>,
+}
+
+/// XML 消息
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Xml {
+    pub xml: String,
+}
+
+/// JSON 消息
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Json {
+    pub json: String,
+}
+
+/// 小程序
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct App {
+    pub content: String,
+}
+
+/// 戳一戳
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Poke {
+    pub name: PokeName,
+}
+
+/// 戳一戳的种类
+#[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PokeName {
+    Poke,
+    ShowLove,
+    Like,
+    Heartbroken,
+    SixSixSix,
+    FangDaZhao,
+}
+
+/// 骰子
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Dice {
+    pub value: u8,
+}
+
+/// 商城表情
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct MarketFace {
+    pub id: u32,
+    pub name: String,
+}
+
+/// 音乐分享
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct MusicShare {
+    pub kind: String,
+    pub title: String,
+    pub summary: String,
+    pub jump_url: String,
+    pub picture_url: String,
+    pub music_url: String,
+    pub brief: String,
+}
+
+/// 转发消息
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct Forward {
+    pub node_list: Vec<ForwardNode>,
+}
+
+/// 转发消息中的一条消息，`message_chain` 中的消息可以通过 `message_chain_loader` 加载，
+/// 发送时可以只使用 messageId，从缓存中读取一条消息作为节点，因此其余字段都可以为空
+#[derive(IntoChainMeta, FromChainMeta, Debug, PartialEq, Clone)]
+pub struct ForwardNode {
+    pub sender_id: Option<u64>,
+    pub time: Option<i64>,
+    pub sender_name: Option<String>,
+    pub message_chain: Option<Vec<ChainMeta>>,
+    pub message_id: Option<i32>,
+}
+
+/// 文件
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct File {
+    pub id: String,
+    pub name: String,
+    pub size: u64,
+}
+
+/// mirai 码
+#[derive(MessageChain, LoadFormMap, Debug, PartialEq, Clone)]
+pub struct MiraiCode {
+    pub code: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::UnknownPolicy;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_load_mirai_chain() {
+        let json = r#"[
+            {"type": "Source", "id": 123456, "time": 1620000000},
+            {"type": "At", "target": 1234567, "display": "@Mirai"},
+            {"type": "Plain", "text": "好耶"},
+            {"type": "Face", "faceId": 123, "name": "bu"},
+            {"type": "Image", "imageId": "{01E9451B-70ED-EAE3-B37C-101F1EEBF5B5}.mirai", "url": "https://xxx", "path": null, "base64": null},
+            {"type": "Dice", "value": 6},
+            {"type": "MusicShare", "kind": "NeteaseCloudMusic", "title": "t", "summary": "s",
+             "jumpUrl": "j", "pictureUrl": "p", "musicUrl": "m", "brief": "b"},
+            {"type": "Forward", "nodeList": [{"senderId": 123, "time": 0, "senderName": "a", "messageChain": []}]}
+        ]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let mut res = deserialize_message_chain(&mut de, UnknownPolicy::Error).unwrap();
+
+        let source = res.remove(0).into_target::<Source>();
+        assert_eq!(
+            source,
+            Some(Source {
+                id: 123456,
+                time: 1620000000
+            })
+        );
+
+        assert_eq!(res.len(), 7);
+        assert_eq!(
+            res[0].into_target::<At>(),
+            Some(At {
+                target: 1234567,
+                display: Some("@Mirai".to_string())
+            })
+        );
+        assert_eq!(
+            res[2].into_target::<Face>(),
+            Some(Face {
+                face_id: Some(123),
+                name: Some("bu".to_string())
+            })
+        );
+        assert_eq!(res[4].into_target::<Dice>(), Some(Dice { value: 6 }));
+        assert_eq!(
+            res[5].into_target::<MusicShare>().unwrap().jump_url,
+            "j".to_string()
+        );
+        let forward = res[6].into_target::<Forward>().unwrap();
+        assert_eq!(forward.node_list[0].sender_name.as_deref(), Some("a"));
+        assert_eq!(forward.node_list[0].message_id, None);
+    }
+
+    #[test]
+    fn test_nested_objects() {
+        let poke = Poke {
+            name: PokeName::SixSixSix,
+        };
+        let map = crate::map_generate!(&poke);
+        assert_eq!(map.get("name"), Some(&ChainMeta::Str("SixSixSix".to_string())));
+        assert_eq!(message_chain_loader(&map).unwrap().into_target(), Some(poke));
+
+        let map = crate::map_generate!(Poke=>[name: "Hug"]);
+        let err = Poke::try_load_from_map(&map).unwrap_err();
+        assert_eq!(err.path_string(), "name");
+
+        let node = ForwardNode {
+            sender_id: Some(123),
+            time: Some(0),
+            sender_name: Some("a".to_string()),
+            message_chain: Some(vec![]),
+            message_id: None,
+        };
+        let forward = Forward {
+            node_list: vec![node],
+        };
+        let map = crate::map_generate!(&forward);
+        assert_eq!(message_chain_loader(&map).unwrap().into_target(), Some(forward));
+
+        let map: HashMap<String, ChainMeta> =
+            serde_json::from_str(r#"{"type":"Forward","nodeList":[{"messageId":42}]}"#).unwrap();
+        let forward = Forward::try_load_from_map(&map).unwrap();
+        assert_eq!(forward.node_list[0].message_id, Some(42));
+        assert_eq!(forward.node_list[0].sender_id, None);
+    }
+
+    #[test]
+    fn test_mirai_round_trip() {
+        let source = Source { id: -1, time: 1620000000 };
+        let map = crate::map_generate!(&source);
+
+        let res = message_chain_loader(&map).unwrap();
+        assert_eq!(res.into_target::<Source>(), Some(source));
+
+        let flash = FlashImage {
+            image_id: Some("/f8f1ab55-bf8e-4236-b55e-955848d7069f".to_string()),
+            url: None,
+            path: None,
+            base64: None,
+        };
+        let map = crate::map_generate!(&flash);
+        let res = message_chain_loader(&map).unwrap();
+        assert_eq!(res.get_type(), "FlashImage");
+        assert_eq!(res.into_target::<Image>(), None);
+        assert_eq!(res.into_target::<FlashImage>(), Some(flash));
+    }
+}
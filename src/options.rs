@@ -0,0 +1,34 @@
+/// Load Options:
+///  how strict the loading is, pass to the `_with` loaders
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct LoadOptions {
+    numeric_strings: bool,
+    lenient: bool,
+}
+
+impl LoadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// numbers can be loaded from string, like `"1141451919"`
+    pub fn numeric_strings(mut self, enable: bool) -> Self {
+        self.numeric_strings = enable;
+        self
+    }
+
+    pub fn is_numeric_strings(&self) -> bool {
+        self.numeric_strings
+    }
+
+    /// elements of `Vec`, map and other collections failed to load are dropped,
+    /// instead of failing the whole collection
+    pub fn lenient(mut self, enable: bool) -> Self {
+        self.lenient = enable;
+        self
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+}
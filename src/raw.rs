@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::{Serialize, Serializer};
+
+use crate::{ChainError, ChainMeta, LoadFormMap, LoadOptions, MessageChain};
+
+/// Raw Chain:
+///  a chain whose `type` is not known, all data kept as it is
+#[derive(Debug, PartialEq, Clone)]
+pub struct RawChain {
+    ty: String,
+    data: IndexMap<String, ChainMeta>,
+}
+
+impl RawChain {
+    /// the `type` key in `data` is dropped, `ty` is used instead
+    pub fn new<S: Into<String>>(ty: S, mut data: IndexMap<String, ChainMeta>) -> Self {
+        data.shift_remove("type");
+        Self { ty: ty.into(), data }
+    }
+
+    /// build from a loaded map, the `type` key become the type of chain,
+    /// `HashMap` has no order to keep, so keys are sorted to be the same every time,
+    /// chains from `deserialize_message_chain` use `from_ordered` and keep the received order
+    pub fn from_map(map: &HashMap<String, ChainMeta>) -> Self {
+        let mut data: IndexMap<_, _> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        data.sort_keys();
+        Self::from_ordered(data)
+    }
+
+    /// build from a map in received order, the order is kept
+    pub fn from_ordered(mut data: IndexMap<String, ChainMeta>) -> Self {
+        let ty = match data.shift_remove("type") {
+            Some(ChainMeta::Str(ty)) => ty,
+            _ => String::new(),
+        };
+        Self { ty, data }
+    }
+
+    pub fn data(&self) -> &IndexMap<String, ChainMeta> {
+        &self.data
+    }
+
+    pub fn into_data(self) -> IndexMap<String, ChainMeta> {
+        self.data
+    }
+}
+
+impl MessageChain for RawChain {
+    fn get_type(&self) -> &str {
+        &self.ty
+    }
+
+    fn get(&self, key: &str) -> Option<ChainMeta> {
+        self.data.get(key).cloned()
+    }
+
+    fn all_keys(&self) -> Vec<&str> {
+        self.data.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+/// any map with a `type` can be loaded as `RawChain`
+impl LoadFormMap for RawChain {
+    fn try_load_from_map_with(
+        map: &HashMap<String, ChainMeta>,
+        _options: &LoadOptions,
+    ) -> Result<Self, ChainError> {
+        if Self::can_match(map) {
+            Ok(Self::from_map(map))
+        } else {
+            Err(ChainError::unmatched(map))
+        }
+    }
+
+    /// keep the received order, so the chain is forwarded as it is
+    fn try_load_from_ordered(map: IndexMap<String, ChainMeta>) -> Result<Self, ChainError> {
+        match map.get("type") {
+            Some(ChainMeta::Str(_)) => Ok(Self::from_ordered(map)),
+            _ => Err(ChainError::unmatched(&map.into_iter().collect())),
+        }
+    }
+
+    fn can_match(map: &HashMap<String, ChainMeta>) -> bool {
+        matches!(map.get("type"), Some(ChainMeta::Str(_)))
+    }
+
+    fn type_eq(_ty: &str) -> bool {
+        true
+    }
+}
+
+/// serialize as it is received, `type` along with all the data
+impl Serialize for RawChain {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self as &dyn MessageChain).serialize(serializer)
+    }
+}
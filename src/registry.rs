@@ -0,0 +1,217 @@
+use std::any::{type_name, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::DeserializeSeed;
+use serde::Deserializer;
+
+use crate::seq::load_ordered;
+use crate::{
+    ChainError, ChainFallback, ChainMeta, ChainSeqSeed, LoadFormMap, LoadOptions, MessageChain,
+    UnknownPolicy,
+};
+
+type Loader =
+    fn(&HashMap<String, ChainMeta>, &LoadOptions) -> Result<Box<dyn MessageChain>, ChainError>;
+
+fn load<T: LoadFormMap + 'static>(
+    map: &HashMap<String, ChainMeta>,
+    options: &LoadOptions,
+) -> Result<Box<dyn MessageChain>, ChainError> {
+    Ok(Box::new(T::try_load_from_map_with(map, options)?))
+}
+
+/// one registered type
+#[derive(Clone, Copy)]
+struct Entry {
+    id: TypeId,
+    name: &'static str,
+    can_match: fn(&HashMap<String, ChainMeta>) -> bool,
+    load: Loader,
+    load_ordered: ChainFallback,
+}
+
+impl Entry {
+    fn of<T: LoadFormMap + 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: type_name::<T>(),
+            can_match: T::can_match,
+            load: load::<T>,
+            load_ordered: load_ordered::<T>,
+        }
+    }
+}
+
+/// two different types registered for the same `type`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DuplicateType {
+    pub ty: String,
+    pub registered: &'static str,
+    pub incoming: &'static str,
+}
+
+impl fmt::Display for DuplicateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "message type `{}` registered by both {} and {}",
+            self.ty, self.registered, self.incoming
+        )
+    }
+}
+
+impl std::error::Error for DuplicateType {}
+
+/// Chain Registry:
+///  types implementing `LoadFormMap` registered at runtime,
+///  chains are dispatched by `type` with a single lookup
+#[derive(Clone, Default)]
+pub struct ChainRegistry {
+    by_type: HashMap<&'static str, Entry>,
+    /// types without known `type_tags`, tried by `can_match` after `by_type`
+    fallback: Vec<Entry>,
+}
+
+impl ChainRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// register `T` for all its `type_tags`,
+    /// fail if any of them already belongs to another type
+    pub fn register<T: LoadFormMap + 'static>(&mut self) -> Result<&mut Self, DuplicateType> {
+        let (tags, entry) = (T::type_tags(), Entry::of::<T>());
+        for tag in tags.iter() {
+            self.check(tag, &entry)?;
+        }
+        self.insert(tags, entry);
+        Ok(self)
+    }
+
+    /// register `T`, replacing types already registered for the same `type`
+    pub fn register_override<T: LoadFormMap + 'static>(&mut self) -> &mut Self {
+        self.insert(T::type_tags(), Entry::of::<T>());
+        self
+    }
+
+    /// add all types of `other`, fail if both have a different type for the same `type`
+    pub fn merge(&mut self, other: &ChainRegistry) -> Result<&mut Self, DuplicateType> {
+        for (tag, entry) in other.by_type.iter() {
+            self.check(tag, entry)?;
+        }
+        self.layer(other);
+        Ok(self)
+    }
+
+    /// add all types of `other` on top of this one, `other` wins on the same `type`
+    pub fn layer(&mut self, other: &ChainRegistry) -> &mut Self {
+        for (tag, entry) in other.by_type.iter() {
+            self.by_type.insert(tag, *entry);
+        }
+        for entry in other.fallback.iter() {
+            self.push_fallback(*entry);
+        }
+        self
+    }
+
+    /// whether a type is registered for `ty`
+    pub fn contains(&self, ty: &str) -> bool {
+        self.by_type.contains_key(ty)
+    }
+
+    pub fn try_load_with(
+        &self,
+        map: &HashMap<String, ChainMeta>,
+        options: &LoadOptions,
+    ) -> Result<Box<dyn MessageChain>, ChainError> {
+        match self.entry_of(map).or_else(|| self.fallback_of(map)) {
+            Some(entry) => (entry.load)(map, options),
+            None => Err(ChainError::unmatched(map)),
+        }
+    }
+
+    pub fn try_load(
+        &self,
+        map: &HashMap<String, ChainMeta>,
+    ) -> Result<Box<dyn MessageChain>, ChainError> {
+        self.try_load_with(map, &LoadOptions::default())
+    }
+
+    pub fn load(&self, map: &HashMap<String, ChainMeta>) -> Option<Box<dyn MessageChain>> {
+        self.try_load(map).ok()
+    }
+
+    /// deserialize a whole messageChain array, like `deserialize_message_chain`,
+    /// the fallback types are given the chain in received order
+    pub fn deserialize<'de, D>(
+        &self,
+        deserializer: D,
+        policy: UnknownPolicy,
+    ) -> Result<Vec<Box<dyn MessageChain>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let options = &LoadOptions::default();
+        let known = |map: &HashMap<String, ChainMeta>| match self.entry_of(map) {
+            Some(entry) => (entry.load)(map, options),
+            None => Err(ChainError::unmatched(map)),
+        };
+        ChainSeqSeed::new(known, policy)
+            .fallback(|map| self.fallback_of(map).map(|entry| entry.load_ordered))
+            .deserialize(deserializer)
+    }
+
+    /// the type registered for the `type` of `map`
+    fn entry_of(&self, map: &HashMap<String, ChainMeta>) -> Option<&Entry> {
+        match map.get("type") {
+            Some(ChainMeta::Str(ty)) => self.by_type.get(ty.as_str()),
+            _ => None,
+        }
+    }
+
+    /// the first type without `type_tags` matching `map`
+    fn fallback_of(&self, map: &HashMap<String, ChainMeta>) -> Option<&Entry> {
+        self.fallback.iter().find(|entry| (entry.can_match)(map))
+    }
+
+    fn check(&self, tag: &str, entry: &Entry) -> Result<(), DuplicateType> {
+        match self.by_type.get(tag) {
+            Some(old) if old.id != entry.id => Err(DuplicateType {
+                ty: tag.to_string(),
+                registered: old.name,
+                incoming: entry.name,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn insert(&mut self, tags: Vec<&'static str>, entry: Entry) {
+        if tags.is_empty() {
+            self.push_fallback(entry);
+        }
+        for tag in tags {
+            self.by_type.insert(tag, entry);
+        }
+    }
+
+    fn push_fallback(&mut self, entry: Entry) {
+        if self.fallback.iter().all(|e| e.id != entry.id) {
+            self.fallback.push(entry);
+        }
+    }
+}
+
+impl fmt::Debug for ChainRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut types: Vec<_> = self.by_type.iter().map(|(k, v)| (*k, v.name)).collect();
+        types.sort_unstable();
+        f.debug_struct("ChainRegistry")
+            .field("types", &types)
+            .field(
+                "fallback",
+                &self.fallback.iter().map(|e| e.name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use indexmap::IndexMap;
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::Deserializer;
+
+use crate::{ChainError, ChainMeta, ErrorKind, LoadFormMap, MessageChain, RawChain};
+
+/// what to do with a chain whose `type` is not registered
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum UnknownPolicy {
+    /// drop the chain
+    Skip,
+    /// fail the whole message chain
+    #[default]
+    Error,
+    /// keep the chain as `RawChain`
+    Raw,
+}
+
+/// load a chain of unknown `type` from the map in received order
+pub type ChainFallback =
+    fn(IndexMap<String, ChainMeta>) -> Result<Box<dyn MessageChain>, ChainError>;
+
+/// pick the fallback for a chain of unknown `type`, `None` to follow the policy
+pub type FallbackPicker = fn(&HashMap<String, ChainMeta>) -> Option<ChainFallback>;
+
+/// the `ChainFallback` of `T`
+pub fn load_ordered<T: LoadFormMap + 'static>(
+    map: IndexMap<String, ChainMeta>,
+) -> Result<Box<dyn MessageChain>, ChainError> {
+    Ok(Box::new(T::try_load_from_ordered(map)?))
+}
+
+/// Chain Seq Seed:
+///  deserialize a messageChain array, each element loaded by `loader`
+pub struct ChainSeqSeed<F, P = FallbackPicker> {
+    loader: F,
+    policy: UnknownPolicy,
+    fallback: Option<P>,
+}
+
+impl<F> ChainSeqSeed<F>
+where
+    F: Fn(&HashMap<String, ChainMeta>) -> Result<Box<dyn MessageChain>, ChainError>,
+{
+    pub fn new(loader: F, policy: UnknownPolicy) -> Self {
+        Self {
+            loader,
+            policy,
+            fallback: None,
+        }
+    }
+}
+
+impl<F, P> ChainSeqSeed<F, P>
+where
+    F: Fn(&HashMap<String, ChainMeta>) -> Result<Box<dyn MessageChain>, ChainError>,
+    P: Fn(&HashMap<String, ChainMeta>) -> Option<ChainFallback>,
+{
+    /// chains of unknown `type` are loaded by the fallback `pick` returns,
+    /// in received order, the policy is used if it returns `None`
+    pub fn fallback<Q>(self, pick: Q) -> ChainSeqSeed<F, Q>
+    where
+        Q: Fn(&HashMap<String, ChainMeta>) -> Option<ChainFallback>,
+    {
+        ChainSeqSeed {
+            loader: self.loader,
+            policy: self.policy,
+            fallback: Some(pick),
+        }
+    }
+
+    /// `ordered` is the chain in received order, kept by `RawChain`
+    fn load(
+        &self,
+        ordered: IndexMap<String, ChainMeta>,
+    ) -> Result<Option<Box<dyn MessageChain>>, ChainError> {
+        let keys: Vec<String> = ordered.keys().cloned().collect();
+        let mut map: HashMap<String, ChainMeta> = ordered.into_iter().collect();
+        let err = match (self.loader)(&map) {
+            Ok(chain) => return Ok(Some(chain)),
+            Err(err) => err,
+        };
+        if !matches!(err.kind(), ErrorKind::UnknownType(_)) {
+            return Err(err);
+        }
+        let fallback = self.fallback.as_ref().and_then(|pick| pick(&map));
+        let mut ordered = || {
+            keys.iter()
+                .filter_map(|k| map.remove_entry(k))
+                .collect::<IndexMap<_, _>>()
+        };
+        match (fallback, self.policy) {
+            (Some(fallback), _) => fallback(ordered()).map(Some),
+            (None, UnknownPolicy::Skip) => Ok(None),
+            (None, UnknownPolicy::Raw) => Ok(Some(Box::new(RawChain::from_ordered(ordered())))),
+            (None, UnknownPolicy::Error) => Err(err),
+        }
+    }
+}
+
+impl<'de, F, P> DeserializeSeed<'de> for ChainSeqSeed<F, P>
+where
+    F: Fn(&HashMap<String, ChainMeta>) -> Result<Box<dyn MessageChain>, ChainError>,
+    P: Fn(&HashMap<String, ChainMeta>) -> Option<ChainFallback>,
+{
+    type Value = Vec<Box<dyn MessageChain>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(ChainSeqVisitor {
+            seed: self,
+            _p: PhantomData,
+        })
+    }
+}
+
+struct ChainSeqVisitor<'de, F, P> {
+    seed: ChainSeqSeed<F, P>,
+    _p: PhantomData<&'de ()>,
+}
+
+impl<'de, F, P> Visitor<'de> for ChainSeqVisitor<'de, F, P>
+where
+    F: Fn(&HashMap<String, ChainMeta>) -> Result<Box<dyn MessageChain>, ChainError>,
+    P: Fn(&HashMap<String, ChainMeta>) -> Option<ChainFallback>,
+{
+    type Value = Vec<Box<dyn MessageChain>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a message chain array")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut chains = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        let mut index = 0;
+        while let Some(map) = seq.next_element::<IndexMap<String, ChainMeta>>()? {
+            let chain = self
+                .seed
+                .load(map)
+                .map_err(|e| de::Error::custom(e.at_index(index)))?;
+            chains.extend(chain);
+            index += 1;
+        }
+        Ok(chains)
+    }
+}
+
+/// deserialize a whole messageChain array using `loader` for every element
+pub fn deserialize_chain<'de, D, F>(
+    deserializer: D,
+    loader: F,
+    policy: UnknownPolicy,
+) -> Result<Vec<Box<dyn MessageChain>>, D::Error>
+where
+    D: Deserializer<'de>,
+    F: Fn(&HashMap<String, ChainMeta>) -> Result<Box<dyn MessageChain>, ChainError>,
+{
+    ChainSeqSeed::new(loader, policy).deserialize(deserializer)
+}
@@ -0,0 +1,99 @@
+/// derive without any `use`, all paths in generated code are absolute
+mod chains {
+    #[derive(msg_chain::MessageChain, msg_chain::LoadFormMap, Default, Debug, PartialEq)]
+    pub struct Plain {
+        pub text: String,
+    }
+
+    #[msg_chain::chain_meta_limit]
+    #[derive(msg_chain::MessageChain, msg_chain::LoadFormMap, Debug, PartialEq)]
+    pub struct Face {
+        #[limit(range(0..=300))]
+        pub face_id: u32,
+        pub name: Option<String>,
+    }
+
+    #[derive(msg_chain::MessageChain, msg_chain::LoadFormMap, Debug, PartialEq)]
+    pub enum Segment {
+        Plain(Plain),
+        Dice { value: u8 },
+        AtAll,
+    }
+
+    msg_chain::msg_loader_generate!(pub fn loader => Plain, Face);
+}
+
+/// loaders still compile with common std names shadowed
+#[allow(dead_code)]
+mod shadowed {
+    type Result<T> = std::result::Result<T, String>;
+    struct Box;
+    struct String;
+    struct Option;
+    struct Vec;
+
+    #[derive(msg_chain::MessageChain, msg_chain::LoadFormMap)]
+    pub struct Plain {
+        pub text: std::string::String,
+    }
+
+    msg_chain::msg_loader_generate!(pub fn loader => Plain; fallback = msg_chain::RawChain);
+    msg_chain::msg_loader_generate!(Plain);
+}
+
+/// `msg_chain` re-exported by another crate
+mod framework {
+    pub use msg_chain as chain;
+
+    #[derive(chain::MessageChain, chain::LoadFormMap, Debug, PartialEq)]
+    #[meta(crate = "crate::framework::chain")]
+    pub struct Poke {
+        pub name: String,
+    }
+}
+
+use msg_chain::{ChainMeta, IntoChainMeta, LoadFormMap, MessageChain};
+use std::collections::HashMap;
+
+#[test]
+fn test_plain() {
+    let map = msg_chain::map_generate!(Plain=>[text: "好耶"]);
+
+    let res = chains::loader(&map).unwrap();
+    let p = res.into_target::<chains::Plain>().unwrap();
+
+    assert_eq!(
+        p,
+        chains::Plain {
+            text: "好耶".to_string()
+        }
+    )
+}
+
+#[test]
+fn test_shadowed_loader() {
+    let map = msg_chain::map_generate!(Plain=>[text: "好耶"]);
+    assert_eq!(shadowed::loader(&map).unwrap().get_type(), "Plain");
+    assert!(shadowed::message_chain_loader(&map).is_some());
+
+    let map = msg_chain::map_generate!(Dice=>[value: 6_u8]);
+    assert_eq!(shadowed::loader(&map).unwrap().get_type(), "Dice");
+    assert!(shadowed::try_message_chain_loader(&map).is_err());
+}
+
+#[test]
+fn test_derive_paths() {
+    let map = msg_chain::map_generate!(Face=>[faceId: 301_u32]);
+    assert!(chains::loader(&map).is_err());
+
+    let map = msg_chain::map_generate!(Dice=>[value: 6_u8]);
+    let dice = chains::Segment::try_load_from_map(&map).unwrap();
+    assert_eq!(dice, chains::Segment::Dice { value: 6 });
+    assert_eq!(dice.get("value"), Some(6_u8.into_chain()));
+
+    let poke = framework::Poke {
+        name: "ChuoYiChuo".to_string(),
+    };
+    let map = msg_chain::map_generate!(&poke);
+    assert_eq!(framework::Poke::try_load_from_map(&map), Ok(poke));
+}
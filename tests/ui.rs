@@ -0,0 +1,20 @@
+/// diagnostics of the derives and `chain_meta_limit`, each case in `tests/ui`
+#[test]
+fn derive_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/derive_*.rs");
+}
+
+/// impls written before `try_from_chain`/`try_load_from_map_with` became required
+#[test]
+fn trait_required() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/trait_*.rs");
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn limit_regex() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/limit_bad_regex.rs");
+}
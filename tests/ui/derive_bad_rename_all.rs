@@ -0,0 +1,9 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+#[meta(rename_all = "kebab-case")]
+struct Image {
+    image_id: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+struct Plain {
+    #[meta(rename = "a", rename = "b")]
+    text: String,
+}
+
+fn main() {}
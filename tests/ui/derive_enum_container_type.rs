@@ -0,0 +1,9 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+#[meta(type = "Segment")]
+enum Segment {
+    AtAll,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+enum Segment {
+    AtAll,
+    #[meta(type = "AtAll")]
+    All,
+}
+
+fn main() {}
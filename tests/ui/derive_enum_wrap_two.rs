@@ -0,0 +1,13 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+struct Plain {
+    text: String,
+}
+
+#[derive(MessageChain, LoadFormMap)]
+enum Segment {
+    Two(Plain, Plain),
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+struct Plain {
+    text: String,
+}
+
+#[derive(MessageChain, LoadFormMap)]
+enum Segment {
+    #[meta(type = "Text")]
+    Plain(Plain),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+struct Image {
+    image_id: String,
+    #[meta(rename = "imageId")]
+    url: String,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+struct Dice(u8);
+
+fn main() {}
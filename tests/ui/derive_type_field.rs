@@ -0,0 +1,8 @@
+use msg_chain::{LoadFormMap, MessageChain};
+
+#[derive(MessageChain, LoadFormMap)]
+struct Poke {
+    r#type: String,
+}
+
+fn main() {}
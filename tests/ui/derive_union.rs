@@ -0,0 +1,8 @@
+use msg_chain::MessageChain;
+
+#[derive(MessageChain)]
+union Plain {
+    text: u32,
+}
+
+fn main() {}
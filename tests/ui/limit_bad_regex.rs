@@ -0,0 +1,10 @@
+use msg_chain::{chain_meta_limit, LoadFormMap, MessageChain};
+
+#[chain_meta_limit]
+#[derive(MessageChain, LoadFormMap)]
+struct Plain {
+    #[limit(regex = "[0-9")]
+    text: String,
+}
+
+fn main() {}
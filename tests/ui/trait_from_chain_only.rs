@@ -0,0 +1,11 @@
+use msg_chain::{ChainMeta, FromChainMeta};
+
+struct Even(u64);
+
+impl FromChainMeta for Even {
+    fn from_chain(chain: Option<&ChainMeta>) -> Option<Self> {
+        u64::from_chain(chain).filter(|n| n % 2 == 0).map(Even)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+
+use msg_chain::{ChainMeta, LoadFormMap, MessageChain};
+
+struct Ping;
+
+impl MessageChain for Ping {
+    fn get_type(&self) -> &str {
+        "Ping"
+    }
+    fn get(&self, _key: &str) -> Option<ChainMeta> {
+        None
+    }
+    fn all_keys(&self) -> Vec<&str> {
+        Vec::new()
+    }
+}
+
+impl LoadFormMap for Ping {
+    fn load_from_map(map: &HashMap<String, ChainMeta>) -> Option<Self> {
+        Self::can_match(map).then_some(Ping)
+    }
+    fn can_match(map: &HashMap<String, ChainMeta>) -> bool {
+        map.get("type").and_then(ChainMeta::as_str).map_or(false, Self::type_eq)
+    }
+    fn type_eq(ty: &str) -> bool {
+        ty == "Ping"
+    }
+}
+
+fn main() {}